@@ -0,0 +1,74 @@
+// Compares day 3's two rating filters on a large random report: scanning the
+// rows at every step, against popcounts over bit-planes.
+//
+//     cargo bench --bench day_3 -- [-n ITERATIONS] [ROWS [WIDTH]]
+//
+// The report has 1000000 rows of 24 bits unless told otherwise.
+
+use std::env;
+use std::hint::black_box;
+use std::process;
+
+use advent_of_code_2021::bench::{self, time};
+use advent_of_code_2021::day_3::{self, BitCriteria, BitPlanes};
+use advent_of_code_2021::generate::Rng;
+
+const USAGE: &str = "usage: day_3 [-n ITERATIONS] [ROWS [WIDTH]]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn main() {
+    let (iterations, sizes) =
+        bench::size_args(env::args().skip(1), 5, 2).unwrap_or_else(|| usage());
+    let rows = sizes.first().copied().unwrap_or(1_000_000);
+    let width = sizes.get(1).copied().unwrap_or(24);
+
+    let mut rng = Rng::new(3);
+    let mut input = String::with_capacity(rows * (width + 1));
+    for _ in 0..rows {
+        input.extend((0..width).map(|_| if rng.chance(1, 2) { '1' } else { '0' }));
+        input.push('\n');
+    }
+    let report = day_3::parse(&input).unwrap();
+    let planes = BitPlanes::new(&report);
+    for criteria in [BitCriteria::OXYGEN, BitCriteria::CO2] {
+        assert_eq!(
+            planes.filter(criteria),
+            day_3::filter(&report, criteria),
+            "the filters disagree"
+        );
+    }
+
+    println!("{} rows of {} bits", rows, width);
+    let results = [
+        (
+            "rows",
+            time(iterations, || {
+                for criteria in [BitCriteria::OXYGEN, BitCriteria::CO2] {
+                    black_box(day_3::filter(black_box(&report), criteria));
+                }
+            }),
+        ),
+        (
+            "planes",
+            time(iterations, || {
+                for criteria in [BitCriteria::OXYGEN, BitCriteria::CO2] {
+                    black_box(black_box(&planes).filter(criteria));
+                }
+            }),
+        ),
+        (
+            "planes + build",
+            time(iterations, || {
+                let planes = BitPlanes::new(black_box(&report));
+                for criteria in [BitCriteria::OXYGEN, BitCriteria::CO2] {
+                    black_box(planes.filter(criteria));
+                }
+            }),
+        ),
+    ];
+    bench::print_table("filter", &results);
+}
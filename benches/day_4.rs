@@ -0,0 +1,73 @@
+// Compares two ways of playing a large bingo tournament to the end: checking
+// every number against every board, against looking each number up in an
+// index of where it appears.
+//
+//     cargo bench --bench day_4 -- [-n ITERATIONS] [BOARDS [SIZE [RANGE]]]
+//
+// By default 5000 boards of 5x5, with numbers from 0 to 9999.
+
+use std::env;
+use std::hint::black_box;
+use std::process;
+
+use advent_of_code_2021::bench::{self, time};
+use advent_of_code_2021::day_4::{self, Board};
+use advent_of_code_2021::generate::Rng;
+
+const USAGE: &str = "usage: day_4 [-n ITERATIONS] [BOARDS [SIZE [RANGE]]]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn main() {
+    let (iterations, sizes) =
+        bench::size_args(env::args().skip(1), 5, 3).unwrap_or_else(|| usage());
+    let count = sizes.first().copied().unwrap_or(5000);
+    let size = sizes.get(1).copied().unwrap_or(5);
+    let range = sizes.get(2).copied().unwrap_or(10_000);
+    if range < size * size {
+        eprintln!("the range must have at least {} numbers", size * size);
+        process::exit(2);
+    }
+
+    let mut rng = Rng::new(4);
+    let mut numbers = (0..range as u32).collect::<Vec<_>>();
+    let boards = (0..count)
+        .map(|_| {
+            rng.shuffle(&mut numbers);
+            Board::new(size, numbers[..size * size].to_vec())
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut numbers);
+
+    assert_eq!(
+        day_4::play(&numbers, boards.clone()).finishes(),
+        day_4::play_scanning(&numbers, boards.clone()),
+        "the engines disagree"
+    );
+
+    println!(
+        "{} boards of {}x{}, numbers below {}",
+        count, size, size, range
+    );
+    let results = [
+        (
+            "scan",
+            time(iterations, || {
+                black_box(day_4::play_scanning(
+                    black_box(&numbers),
+                    black_box(boards.clone()),
+                ));
+            }),
+        ),
+        (
+            "index",
+            time(iterations, || {
+                black_box(day_4::play(black_box(&numbers), black_box(boards.clone())));
+            }),
+        ),
+    ];
+    bench::print_table("engine", &results);
+}
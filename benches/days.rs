@@ -0,0 +1,143 @@
+// Times parse, part 1 and part 2 of every day against `src/input/day_N.txt`.
+//
+//     cargo bench --bench days -- [-n ITERATIONS] [--save FILE]
+//         [--compare FILE] [--threshold PERCENT] [DAY...]
+//
+// --save writes the results so a later run can --compare against them; any
+// phase whose median is more than --threshold percent slower (default 10) is
+// reported, and the run exits non-zero.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+use advent_of_code_2021::bench::{self, Results};
+use advent_of_code_2021::registry::{self, SOLUTIONS};
+
+const USAGE: &str = "usage: days [-n ITERATIONS] [--save FILE] [--compare FILE] \
+                     [--threshold PERCENT] [DAY...]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn fmt(d: Duration) -> String {
+    format!("{:.2?}", d)
+}
+
+fn main() {
+    let mut iterations = 10;
+    let mut save = None;
+    let mut baseline = None;
+    let mut threshold = 10.0;
+    let mut days = vec![];
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // passed along by `cargo bench`
+            "--bench" => (),
+            "-n" => {
+                iterations = match args.next().and_then(|v| v.parse().ok()) {
+                    Some(n) if n > 0 => n,
+                    _ => usage(),
+                }
+            }
+            "--save" => save = Some(args.next().unwrap_or_else(|| usage())),
+            "--compare" => baseline = Some(args.next().unwrap_or_else(|| usage())),
+            "--threshold" => {
+                threshold = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            day => match day.parse().ok().and_then(registry::get) {
+                Some(solution) => days.push(solution),
+                None => usage(),
+            },
+        }
+    }
+    if days.is_empty() {
+        days = SOLUTIONS.to_vec();
+    }
+
+    let baseline = baseline.map(|path| {
+        let s = fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("failed to read {}: {}", path, e);
+            process::exit(1);
+        });
+        bench::load(&s).unwrap_or_else(|e| {
+            eprintln!("failed to parse {}: {}", path, e);
+            process::exit(1);
+        })
+    });
+
+    println!(
+        "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}",
+        "day", "phase", "min", "median", "max"
+    );
+
+    let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/input");
+    let mut results = Results::new();
+    for solution in days {
+        let day = solution.day();
+        let path = input_dir.join(format!("day_{}.txt", day));
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("{:>3}  {}: {}", day, path.display(), e);
+                continue;
+            }
+        };
+
+        let phases = match bench::measure(solution, &input, iterations) {
+            Ok(phases) => phases,
+            Err(e) => {
+                println!("{:>3}  {}: {}", day, path.display(), e);
+                continue;
+            }
+        };
+        for (phase, stats) in phases {
+            println!(
+                "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}",
+                day,
+                phase,
+                fmt(stats.min),
+                fmt(stats.median),
+                fmt(stats.max)
+            );
+            results.insert((day, phase), stats);
+        }
+    }
+
+    if let Some(path) = save {
+        if let Err(e) = fs::write(&path, bench::save(&results)) {
+            eprintln!("failed to write {}: {}", path, e);
+            process::exit(1);
+        }
+    }
+
+    if let Some(baseline) = baseline {
+        let regressions = bench::compare(&baseline, &results, threshold / 100.0);
+        if regressions.is_empty() {
+            println!("no regressions over {}%", threshold);
+        } else {
+            println!();
+            println!("regressions over {}%:", threshold);
+            for r in &regressions {
+                println!(
+                    "{:>3}  {:<5}  {:>10} -> {:>10}  ({:.2}x)",
+                    r.day,
+                    r.phase,
+                    fmt(r.before),
+                    fmt(r.after),
+                    r.ratio()
+                );
+            }
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::DynSolution;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        }
+    }
+
+    fn from_name(s: &str) -> Option<Phase> {
+        match s {
+            "parse" => Some(Phase::Parse),
+            "part1" => Some(Phase::Part1),
+            "part2" => Some(Phase::Part2),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarises a set of timings. Panics if `samples` is empty.
+    pub fn new(samples: &[Duration]) -> Self {
+        let mut samples = samples.to_vec();
+        samples.sort_unstable();
+        let mid = samples.len() / 2;
+        let median = if samples.len() % 2 == 1 {
+            samples[mid]
+        } else {
+            (samples[mid - 1] + samples[mid]) / 2
+        };
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Runs `f` `iterations` times, timing each run. `iterations` must be at
+/// least 1.
+pub fn time(iterations: usize, mut f: impl FnMut()) -> Stats {
+    let samples = (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    Stats::new(&samples)
+}
+
+/// Prints named timings as a table, under a heading of `label`.
+pub fn print_table(label: &str, rows: &[(&str, Stats)]) {
+    let fmt = |d: Duration| format!("{:.2?}", d);
+    println!(
+        "{:<16}  {:>10}  {:>10}  {:>10}",
+        label, "min", "median", "max"
+    );
+    for (name, stats) in rows {
+        println!(
+            "{:<16}  {:>10}  {:>10}  {:>10}",
+            name,
+            fmt(stats.min),
+            fmt(stats.median),
+            fmt(stats.max)
+        );
+    }
+}
+
+/// Reads the arguments the single-day benches share: `-n ITERATIONS`, then up
+/// to `max_sizes` positive sizes. `--bench`, which `cargo bench` passes
+/// along, is skipped. Returns the iterations (`iterations` unless given) and
+/// sizes, or `None` if anything else turns up.
+pub fn size_args(
+    args: impl IntoIterator<Item = String>,
+    mut iterations: usize,
+    max_sizes: usize,
+) -> Option<(usize, Vec<usize>)> {
+    let mut sizes = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bench" => (),
+            "-n" => {
+                iterations = args.next()?.parse().ok().filter(|n| *n > 0)?;
+            }
+            size => match size.parse() {
+                Ok(size) if size > 0 && sizes.len() < max_sizes => sizes.push(size),
+                _ => return None,
+            },
+        }
+    }
+    Some((iterations, sizes))
+}
+
+/// Timings keyed by day and phase.
+pub type Results = BTreeMap<(usize, Phase), Stats>;
+
+/// Parses `input` and runs both parts `iterations` times each, timing every
+/// phase separately. `iterations` must be at least 1.
+pub fn measure(
+    solution: &dyn DynSolution,
+    input: &str,
+    iterations: usize,
+) -> Result<Vec<(Phase, Stats)>, ParseError> {
+    let mut parse_times = vec![];
+    // the first parse doubles as a warm-up, and catches bad input early
+    let mut parsed = solution.parse(input)?;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        parsed = black_box(solution.parse(black_box(input))?);
+        parse_times.push(start.elapsed());
+    }
+
+    let mut results = vec![(Phase::Parse, Stats::new(&parse_times))];
+    for (part, phase) in [(1, Phase::Part1), (2, Phase::Part2)] {
+        let mut times = vec![];
+        for _ in 0..iterations {
+            let start = Instant::now();
+            let answer = black_box(solution.part(part, black_box(&*parsed)));
+            times.push(start.elapsed());
+            if answer.is_none() {
+                break;
+            }
+        }
+        if times.len() == iterations {
+            results.push((phase, Stats::new(&times)));
+        }
+    }
+    Ok(results)
+}
+
+/// Writes results as tab-separated `day phase min median max` lines, with
+/// times in nanoseconds.
+pub fn save(results: &Results) -> String {
+    let mut out = String::new();
+    for ((day, phase), stats) in results {
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            day,
+            phase,
+            stats.min.as_nanos(),
+            stats.median.as_nanos(),
+            stats.max.as_nanos()
+        ));
+    }
+    out
+}
+
+pub fn load(s: &str) -> Result<Results, ParseError> {
+    let mut results = Results::new();
+    for line in parse::lines(s) {
+        if line.text.trim().is_empty() {
+            continue;
+        }
+        let mut fields = line.text.split('\t');
+        let day = line.number(line.field(&mut fields)?)?;
+        let phase = line.field(&mut fields)?;
+        let phase =
+            Phase::from_name(phase).ok_or_else(|| line.error(phase, ErrorKind::UnknownKeyword))?;
+        let mut nanos = || -> Result<Duration, ParseError> {
+            Ok(Duration::from_nanos(line.number(line.field(&mut fields)?)?))
+        };
+        let stats = Stats {
+            min: nanos()?,
+            median: nanos()?,
+            max: nanos()?,
+        };
+        results.insert((day, phase), stats);
+    }
+    Ok(results)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Regression {
+    pub day: usize,
+    pub phase: Phase,
+    pub before: Duration,
+    pub after: Duration,
+}
+
+impl Regression {
+    pub fn ratio(&self) -> f64 {
+        self.after.as_secs_f64() / self.before.as_secs_f64()
+    }
+}
+
+/// Flags every phase whose median got slower by more than `threshold`
+/// (0.1 meaning 10%) compared to `baseline`. Phases missing from either side
+/// are skipped.
+pub fn compare(baseline: &Results, current: &Results, threshold: f64) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|(&(day, phase), stats)| {
+            let before = baseline.get(&(day, phase))?.median;
+            let after = stats.median;
+            if after.as_secs_f64() > before.as_secs_f64() * (1.0 + threshold) {
+                Some(Regression {
+                    day,
+                    phase,
+                    before,
+                    after,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{compare, load, measure, save, size_args, time, Phase, Results, Stats};
+    use crate::registry;
+
+    fn ms(v: u64) -> Duration {
+        Duration::from_millis(v)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(&[ms(5), ms(1), ms(3)]);
+        assert_eq!((stats.min, stats.median, stats.max), (ms(1), ms(3), ms(5)));
+        assert_eq!(Stats::new(&[ms(4), ms(2)]).median, ms(3));
+    }
+
+    #[test]
+    fn test_time() {
+        let mut runs = 0;
+        let stats = time(3, || runs += 1);
+        assert_eq!(runs, 3);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+    }
+
+    #[test]
+    fn test_size_args() {
+        let args = |s: &str| s.split_whitespace().map(str::to_owned).collect::<Vec<_>>();
+        assert_eq!(size_args(args("--bench"), 5, 2), Some((5, vec![])));
+        assert_eq!(size_args(args("-n 3 100 7"), 5, 2), Some((3, vec![100, 7])));
+        assert_eq!(size_args(args("100 7 1"), 5, 2), None);
+        assert_eq!(size_args(args("-n 0"), 5, 2), None);
+        assert_eq!(size_args(args("-n"), 5, 2), None);
+        assert_eq!(size_args(args("0"), 5, 2), None);
+    }
+
+    #[test]
+    fn test_results_round_trip() {
+        let mut results = Results::new();
+        results.insert((1, Phase::Parse), Stats::new(&[ms(1)]));
+        results.insert((25, Phase::Part1), Stats::new(&[ms(2), ms(7)]));
+        let saved = save(&results);
+        assert_eq!(
+            saved.lines().next(),
+            Some("1\tparse\t1000000\t1000000\t1000000")
+        );
+        assert_eq!(load(&saved).unwrap(), results);
+
+        let e = load("1\tpart3\t1\t2\t3").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 3));
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut before = Results::new();
+        before.insert((1, Phase::Part1), Stats::new(&[ms(10)]));
+        before.insert((1, Phase::Part2), Stats::new(&[ms(10)]));
+        let mut after = before.clone();
+        after.insert((1, Phase::Part2), Stats::new(&[ms(12)]));
+        after.insert((2, Phase::Part1), Stats::new(&[ms(100)]));
+
+        let regressions = compare(&before, &after, 0.1);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(
+            (regressions[0].day, regressions[0].phase),
+            (1, Phase::Part2)
+        );
+        assert!((regressions[0].ratio() - 1.2).abs() < 1e-9);
+        assert!(compare(&before, &after, 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_measure() {
+        let day = registry::get(25).unwrap();
+        let phases = measure(day, "v.\nv.", 3)
+            .unwrap()
+            .into_iter()
+            .map(|(phase, _)| phase)
+            .collect::<Vec<_>>();
+        assert_eq!(phases, vec![Phase::Parse, Phase::Part1]);
+        assert!(measure(day, "v.\nx.", 3).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-/// Sonar Sweep
+// --- Day 1: Sonar Sweep ---
 // As the submarine drops below the surface of the ocean, it automatically
 // performs a sonar sweep of the nearby sea floor. On a small screen, the sonar
 // sweep report (your puzzle input) appears: each line is a measurement of the
@@ -90,28 +90,236 @@
 
 // Consider sums of a three-measurement sliding window. How many sums are larger
 // than the previous sum?
-use std::str::FromStr;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::io::BufRead;
 
-pub fn parse(txt: &str) -> Vec<u64> {
-    txt.split_whitespace()
-        .map(|row| u64::from_str(row).unwrap())
+use crate::parse::{self, ParseError, ReadError};
+use crate::solution::Solution;
+
+fn parse_line(line: &parse::Line<'_>) -> Result<Vec<u64>, ParseError> {
+    line.text
+        .split_whitespace()
+        .map(|row| line.number(row))
         .collect()
 }
 
+pub fn parse(txt: &str) -> Result<Vec<u64>, ParseError> {
+    let mut depths = vec![];
+    for line in parse::lines(txt) {
+        depths.extend(parse_line(&line)?);
+    }
+    Ok(depths)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Change {
+    Increased,
+    Decreased,
+    Unchanged,
+}
+
+/// The sum of one sliding window, and how it compares to the window before
+/// it (`None` for the first window).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Window {
+    pub sum: u64,
+    pub change: Option<Change>,
+}
+
+/// Turns a stream of depths into a stream of window sums, keeping only the
+/// current window in memory. See `windows`.
+#[derive(Clone, Debug)]
+pub struct Windows<I> {
+    depths: I,
+    size: usize,
+    window: VecDeque<u64>,
+    sum: u64,
+    previous: Option<u64>,
+}
+
+impl<I: Iterator<Item = u64>> Iterator for Windows<I> {
+    type Item = Window;
+
+    fn next(&mut self) -> Option<Window> {
+        while self.window.len() < self.size {
+            let depth = self.depths.next()?;
+            self.window.push_back(depth);
+            self.sum += depth;
+        }
+
+        let sum = self.sum;
+        let change = self.previous.map(|previous| match sum.cmp(&previous) {
+            Ordering::Greater => Change::Increased,
+            Ordering::Less => Change::Decreased,
+            Ordering::Equal => Change::Unchanged,
+        });
+        self.previous = Some(sum);
+        // Neighbouring windows share all but one depth, so the next sum is
+        // this one with the oldest depth swapped out.
+        self.sum -= self.window.pop_front().unwrap();
+        Some(Window { sum, change })
+    }
+}
+
+/// Yields the sum of every `size` consecutive depths. Panics if `size` is 0.
+pub fn windows<I: IntoIterator<Item = u64>>(depths: I, size: usize) -> Windows<I::IntoIter> {
+    assert!(size > 0, "window size must be at least 1");
+    Windows {
+        depths: depths.into_iter(),
+        size,
+        window: VecDeque::with_capacity(size),
+        sum: 0,
+        previous: None,
+    }
+}
+
 pub fn count_increasing(depths: &[u64], window_size: usize) -> u64 {
-    let mut prev: Option<u64> = None;
-    let mut count = 0;
-
-    for depths_ in depths.windows(window_size) {
-        let sum_depth = depths_.iter().sum();
-        if let Some(prev) = prev {
-            if sum_depth > prev {
-                count += 1;
+    count_increasing_streaming(depths.iter().copied(), window_size)
+}
+
+fn count_increasing_streaming(depths: impl Iterator<Item = u64>, window_size: usize) -> u64 {
+    windows(depths, window_size)
+        .filter(|w| w.change == Some(Change::Increased))
+        .count() as u64
+}
+
+/// A stretch of consecutive windows, by the index of the first one. With a
+/// window size of 1 these are just indices into the depths.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Run {
+    pub start: usize,
+    pub len: usize,
+}
+
+/// The biggest jump between two neighbouring windows, landing on window
+/// `index`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Step {
+    pub index: usize,
+    pub delta: i64,
+}
+
+/// A window whose sum strays too far from the average of the windows just
+/// before it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outlier {
+    pub index: usize,
+    pub sum: u64,
+    pub average: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DepthReport {
+    pub windows: usize,
+    pub increases: usize,
+    pub decreases: usize,
+    pub unchanged: usize,
+    /// The longest strictly increasing run, earliest first on ties.
+    pub longest_increase: Option<Run>,
+    pub longest_decrease: Option<Run>,
+    /// Every run of two or more equal windows.
+    pub plateaus: Vec<Run>,
+    pub max_step: Option<Step>,
+    pub outliers: Vec<Outlier>,
+}
+
+impl DepthReport {
+    fn close(&mut self, change: Change, run: Run) {
+        let longest = match change {
+            Change::Increased => &mut self.longest_increase,
+            Change::Decreased => &mut self.longest_decrease,
+            Change::Unchanged => {
+                self.plateaus.push(run);
+                return;
+            }
+        };
+        if longest.is_none_or(|l| run.len > l.len) {
+            *longest = Some(run);
+        }
+    }
+}
+
+/// Summarises a sweep in a single pass over its `window_size` windows. A
+/// window is an outlier when its sum differs from the mean of the previous
+/// `average_over` sums by more than `tolerance`; the first `average_over`
+/// windows are never outliers.
+pub fn report(
+    depths: impl IntoIterator<Item = u64>,
+    window_size: usize,
+    average_over: usize,
+    tolerance: u64,
+) -> DepthReport {
+    let mut report = DepthReport::default();
+    let mut current: Option<(Change, Run)> = None;
+    let mut recent = VecDeque::with_capacity(average_over);
+    let mut recent_sum = 0u64;
+    let mut previous = 0;
+
+    for (index, window) in windows(depths, window_size).enumerate() {
+        report.windows += 1;
+
+        if let Some(change) = window.change {
+            match change {
+                Change::Increased => report.increases += 1,
+                Change::Decreased => report.decreases += 1,
+                Change::Unchanged => report.unchanged += 1,
+            }
+            match &mut current {
+                Some((c, run)) if *c == change => run.len += 1,
+                _ => {
+                    if let Some((c, run)) = current.take() {
+                        report.close(c, run);
+                    }
+                    let run = Run {
+                        start: index - 1,
+                        len: 2,
+                    };
+                    current = Some((change, run));
+                }
             }
+
+            let delta = window.sum as i64 - previous as i64;
+            if report.max_step.is_none_or(|s| delta.abs() > s.delta.abs()) {
+                report.max_step = Some(Step { index, delta });
+            }
+        }
+        previous = window.sum;
+
+        if average_over > 0 && recent.len() == average_over {
+            // compare in whole numbers: |sum - total / n| > tolerance
+            let scaled = window.sum as u128 * average_over as u128;
+            if scaled.abs_diff(recent_sum as u128) > tolerance as u128 * average_over as u128 {
+                report.outliers.push(Outlier {
+                    index,
+                    sum: window.sum,
+                    average: recent_sum as f64 / average_over as f64,
+                });
+            }
+            recent_sum -= recent.pop_front().unwrap();
         }
-        prev = Some(sum_depth);
+        if average_over > 0 {
+            recent.push_back(window.sum);
+            recent_sum += window.sum;
+        }
+    }
+
+    if let Some((c, run)) = current {
+        report.close(c, run);
     }
-    count
+    report
+}
+
+pub fn read_part_1(r: impl BufRead) -> Result<u64, ReadError> {
+    parse::process_records(parse::records(r, parse_line), |depths| {
+        count_increasing_streaming(depths.flatten(), 1)
+    })
+}
+
+pub fn read_part_2(r: impl BufRead) -> Result<u64, ReadError> {
+    parse::process_records(parse::records(r, parse_line), |depths| {
+        count_increasing_streaming(depths.flatten(), 3)
+    })
 }
 
 pub fn part_1(depths: &[u64]) -> u64 {
@@ -122,31 +330,148 @@ pub fn part_2(depths: &[u64]) -> u64 {
     count_increasing(depths, 3)
 }
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: usize = 1;
+
+    type Input = Vec<u64>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(part_2(input))
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
-    use crate::day_1::{parse, part_1, part_2};
+    use crate::day_1::{
+        parse, part_1, part_2, read_part_1, read_part_2, report, windows, Change, Run, Step, Window,
+    };
+    use crate::parse::ReadError;
 
     #[test]
     pub fn test_day_1_example_part1() {
         let inputs = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
-        assert_eq!(part_1(&parse(inputs)), 7);
+        assert_eq!(part_1(&parse(inputs).unwrap()), 7);
     }
 
     #[test]
     pub fn test_day_1_example_part2() {
         let inputs = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
-        assert_eq!(part_2(&parse(inputs)), 5);
+        assert_eq!(part_2(&parse(inputs).unwrap()), 5);
     }
 
     #[test]
     pub fn test_day_1_part_1() {
         const INPUT_DATA: &str = include_str!("input/day_1.txt");
-        assert_eq!(part_1(&parse(INPUT_DATA)), 1184);
+        assert_eq!(part_1(&parse(INPUT_DATA).unwrap()), 1184);
+    }
+
+    #[test]
+    pub fn test_day_1_parse_error() {
+        let e = parse("199\n200\n20x8\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 1, "20x8"));
     }
 
     #[test]
     pub fn test_day_1_part_2() {
         const INPUT_DATA: &str = include_str!("input/day_1.txt");
-        assert_eq!(part_2(&parse(INPUT_DATA)), 1158);
+        assert_eq!(part_2(&parse(INPUT_DATA).unwrap()), 1158);
+    }
+
+    #[test]
+    pub fn test_day_1_read() {
+        const INPUT_DATA: &str = include_str!("input/day_1.txt");
+        assert_eq!(read_part_1(INPUT_DATA.as_bytes()).unwrap(), 1184);
+        assert_eq!(read_part_2(INPUT_DATA.as_bytes()).unwrap(), 1158);
+        match read_part_1("199\n200\n20x8\n".as_bytes()) {
+            Err(ReadError::Parse(e)) => assert_eq!((e.line, e.column), (3, 1)),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn test_day_1_windows() {
+        let depths = parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263").unwrap();
+        let sums = windows(depths.iter().copied(), 3).collect::<Vec<_>>();
+        assert_eq!(
+            sums.iter().map(|w| w.sum).collect::<Vec<_>>(),
+            vec![607, 618, 618, 617, 647, 716, 769, 792]
+        );
+        assert_eq!(
+            sums[..4].iter().map(|w| w.change).collect::<Vec<_>>(),
+            vec![
+                None,
+                Some(Change::Increased),
+                Some(Change::Unchanged),
+                Some(Change::Decreased)
+            ]
+        );
+
+        // works on unbounded input, only pulling as many depths as needed
+        let mut feed = windows((1..).map(|d| d % 4), 2);
+        assert_eq!(
+            feed.nth(3),
+            Some(Window {
+                sum: 1,
+                change: Some(Change::Decreased)
+            })
+        );
+        assert_eq!(windows(vec![1, 2], 3).next(), None);
+    }
+
+    #[test]
+    pub fn test_day_1_report() {
+        let depths = parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263").unwrap();
+        let r = report(depths.iter().copied(), 1, 3, 20);
+        assert_eq!(
+            (r.windows, r.increases, r.decreases, r.unchanged),
+            (10, 7, 2, 0)
+        );
+        assert_eq!(r.longest_increase, Some(Run { start: 0, len: 4 }));
+        assert_eq!(r.longest_decrease, Some(Run { start: 3, len: 2 }));
+        assert!(r.plateaus.is_empty());
+        assert_eq!(
+            r.max_step,
+            Some(Step {
+                index: 6,
+                delta: 33
+            })
+        );
+        assert_eq!(
+            r.outliers.iter().map(|o| o.index).collect::<Vec<_>>(),
+            vec![6, 7, 8]
+        );
+        assert!((r.outliers[0].average - 617.0 / 3.0).abs() < 1e-9);
+
+        let r = report(depths.iter().copied(), 3, 0, 0);
+        assert_eq!(r.plateaus, vec![Run { start: 1, len: 2 }]);
+        assert_eq!(r.longest_increase, Some(Run { start: 3, len: 5 }));
+        assert!(r.outliers.is_empty());
+
+        let r = report(vec![5, 5, 5, 4, 4], 1, 1, 0);
+        assert_eq!(
+            r.plateaus,
+            vec![Run { start: 0, len: 3 }, Run { start: 3, len: 2 }]
+        );
+        assert_eq!(
+            r.max_step,
+            Some(Step {
+                index: 3,
+                delta: -1
+            })
+        );
+        assert_eq!(r.outliers.len(), 1);
+        assert_eq!(report(vec![], 1, 1, 0), Default::default());
     }
 }
@@ -136,83 +136,148 @@
 // Find the completion string for each incomplete line, score the completion
 // strings, and sort the scores. What is the middle score?
 
-pub fn part_1(s: &str) -> usize {
-    let mut score = 0;
-    for line in s.lines() {
-        let mut stk = vec![];
-        for c in line.chars() {
-            match c {
-                '(' | '{' | '[' | '<' => {
-                    stk.push(c);
-                }
-                ')' | ']' | '}' | '>' => {
-                    let v = stk.pop().unwrap();
-                    let score_ = match (v, c) {
-                        ('(', ')') | ('[', ']') | ('{', '}') | ('<', '>') => 0,
-                        (_, ')') => 3,
-                        (_, ']') => 57,
-                        (_, '}') => 1197,
-                        (_, '>') => 25137,
-                        _ => unreachable!(),
-                    };
-
-                    score += score_;
-                    if score_ != 0 {
-                        break;
-                    }
-                }
-                _ => unreachable!(),
-            }
+use std::io::BufRead;
+
+use crate::parse::{self, ErrorKind, Line, ParseError, ReadError};
+use crate::solution::Solution;
+
+fn check_line<'a>(line: &Line<'a>) -> Result<&'a str, ParseError> {
+    for (c, at) in line.chars() {
+        if !"([{<)]}>".contains(c) {
+            return Err(line.error(at, ErrorKind::UnexpectedChar));
         }
     }
-    score
+    Ok(line.text)
 }
 
-pub fn part_2(s: &str) -> usize {
-    let mut scores = vec![];
-    for line in s.lines() {
-        let mut stk = vec![];
-        let mut skip_line = false;
-        for c in line.chars() {
-            match c {
-                '(' | '{' | '[' | '<' => {
-                    stk.push(c);
-                }
-                ')' | ']' | '}' | '>' => {
-                    let v = stk.pop().unwrap();
-                    match (v, c) {
-                        ('(', ')') | ('[', ']') | ('{', '}') | ('<', '>') => (),
-                        _ => skip_line = true,
-                    };
+pub fn parse(s: &str) -> Result<Vec<String>, ParseError> {
+    parse::lines(s)
+        .map(|line| check_line(&line).map(str::to_owned))
+        .collect()
+}
+
+fn corruption_score(line: &str) -> usize {
+    let mut stk = vec![];
+    for c in line.chars() {
+        match c {
+            '(' | '{' | '[' | '<' => {
+                stk.push(c);
+            }
+            ')' | ']' | '}' | '>' => {
+                let v = stk.pop().unwrap();
+                let score = match (v, c) {
+                    ('(', ')') | ('[', ']') | ('{', '}') | ('<', '>') => 0,
+                    (_, ')') => 3,
+                    (_, ']') => 57,
+                    (_, '}') => 1197,
+                    (_, '>') => 25137,
+                    _ => unreachable!(),
+                };
+
+                if score != 0 {
+                    return score;
                 }
-                _ => unreachable!(),
             }
+            _ => unreachable!(),
         }
+    }
+    0
+}
 
-        if !skip_line {
-            let mut line_score = 0;
-            while let Some(v) = stk.pop() {
-                let score = match v {
-                    '(' => 1,
-                    '[' => 2,
-                    '{' => 3,
-                    '<' => 4,
-                    _ => 0,
+fn completion_score(line: &str) -> Option<usize> {
+    let mut stk = vec![];
+    for c in line.chars() {
+        match c {
+            '(' | '{' | '[' | '<' => {
+                stk.push(c);
+            }
+            ')' | ']' | '}' | '>' => {
+                let v = stk.pop().unwrap();
+                match (v, c) {
+                    ('(', ')') | ('[', ']') | ('{', '}') | ('<', '>') => (),
+                    _ => return None,
                 };
-                if score != 0 {
-                    line_score = line_score * 5 + score;
-                }
             }
-            scores.push(line_score);
+            _ => unreachable!(),
+        }
+    }
+
+    let mut line_score = 0;
+    while let Some(v) = stk.pop() {
+        let score = match v {
+            '(' => 1,
+            '[' => 2,
+            '{' => 3,
+            '<' => 4,
+            _ => 0,
+        };
+        if score != 0 {
+            line_score = line_score * 5 + score;
         }
     }
+    Some(line_score)
+}
+
+fn total_corruption_score(lines: &[String]) -> usize {
+    lines.iter().map(|l| corruption_score(l)).sum()
+}
+
+fn middle_score(scores: impl Iterator<Item = usize>) -> usize {
+    let mut scores = scores.collect::<Vec<_>>();
     scores.sort_unstable();
     scores[scores.len() / 2]
 }
 
+fn middle_completion_score(lines: &[String]) -> usize {
+    middle_score(lines.iter().flat_map(|l| completion_score(l)))
+}
+
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(total_corruption_score(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(middle_completion_score(&parse(s)?))
+}
+
+// The readers score each line as it arrives, so only the completion scores
+// are ever kept around.
+
+pub fn read_part_1(r: impl BufRead) -> Result<usize, ReadError> {
+    let scores = parse::records(r, |line| check_line(line).map(corruption_score));
+    parse::process_records(scores, |scores| scores.sum())
+}
+
+pub fn read_part_2(r: impl BufRead) -> Result<usize, ReadError> {
+    let scores = parse::records(r, |line| check_line(line).map(completion_score));
+    parse::process_records(scores, |scores| middle_score(scores.flatten()))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: usize = 10;
+
+    type Input = Vec<String>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        total_corruption_score(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(middle_completion_score(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2, read_part_1, read_part_2};
 
     const INPUT: &str = r#"[({(<(())[]>[[{[]{<()<>>
 [(()[<>])]({[<{<<[]>>(
@@ -227,21 +292,38 @@ mod tests {
 
     #[test]
     fn test_day_10_example_part_1() {
-        assert_eq!(part_1(INPUT), 26397);
+        assert_eq!(part_1(INPUT).unwrap(), 26397);
     }
 
     #[test]
     fn test_day_10_part_1() {
-        assert_eq!(part_1(include_str!("input/day_10.txt")), 339477);
+        assert_eq!(part_1(include_str!("input/day_10.txt")).unwrap(), 339477);
     }
 
     #[test]
     fn test_day_10_example_part_2() {
-        assert_eq!(part_2(INPUT), 288957);
+        assert_eq!(part_2(INPUT).unwrap(), 288957);
     }
 
     #[test]
     fn test_day_10_part_2() {
-        assert_eq!(part_2(include_str!("input/day_10.txt")), 3049320156);
+        assert_eq!(
+            part_2(include_str!("input/day_10.txt")).unwrap(),
+            3049320156
+        );
+    }
+
+    #[test]
+    fn test_day_10_read() {
+        assert_eq!(read_part_1(INPUT.as_bytes()).unwrap(), 26397);
+        let input = include_str!("input/day_10.txt");
+        assert_eq!(read_part_2(input.as_bytes()).unwrap(), 3049320156);
+        assert!(read_part_1("[(a)]".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_day_10_parse_error() {
+        let e = parse("[({(<(())[]>[[{[]{<()<>>\n[(()[<>]a)]({[<{<<[]>>(").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 9, "a"));
     }
 }
@@ -62,128 +62,95 @@
 
 use std::collections::HashSet;
 
-fn adjacent_points(
-    i: usize,
-    j: usize,
-    max_i: usize,
-    max_j: usize,
-) -> impl Iterator<Item = (usize, usize)> {
-    (-1isize..=1)
-        .flat_map(move |i_off| {
-            (-1isize..=1).map(move |j_off| (i as isize + i_off, j as isize + j_off))
-        })
-        .filter(move |(i_, j_)| {
-            *i_ >= 0
-                && *i_ < max_i as isize
-                && *j_ >= 0
-                && *j_ < max_j as isize
-                && (i as isize, j as isize) != (*i_, *j_)
-        })
-        .map(|(i_, j_)| (i_ as usize, j_ as usize))
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+
+pub fn parse(s: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse_digits(s)
 }
 
-pub fn part_1(s: &str) -> usize {
-    let mut rows: Vec<Vec<u8>> = vec![];
-    for line in s.lines() {
-        let mut row = vec![];
-        for c in line.chars() {
-            row.push(c.to_digit(10).unwrap() as u8);
+// Runs a single step, returning how many octopuses flashed.
+pub fn step(grid: &mut Grid<u8>) -> usize {
+    // Begining of step
+    let mut queue = vec![];
+    for pos in grid.positions() {
+        grid[pos] += 1;
+        if grid[pos] > 9 {
+            queue.push(pos);
         }
-        rows.push(row);
     }
 
-    let mut num_flashes = 0;
-    for _step in 0..100 {
-        // Begining of step
-        let mut queue = vec![];
-        for i in 0..rows.len() {
-            for j in 0..rows[0].len() {
-                rows[i][j] += 1;
-                if rows[i][j] > 9 {
-                    queue.push((i, j));
-                }
-            }
+    let mut has_flashed = HashSet::new();
+    while let Some(pos) = queue.pop() {
+        if has_flashed.contains(&pos) {
+            continue;
         }
+        assert!(has_flashed.insert(pos));
 
-        let mut has_flashed = HashSet::new();
-        while let Some((i, j)) = queue.pop() {
-            if has_flashed.contains(&(i, j)) {
-                continue;
-            }
-            assert!(has_flashed.insert((i, j)));
-
-            for (i_, j_) in adjacent_points(i, j, rows.len(), rows[0].len()) {
-                rows[i_][j_] += 1;
-                if rows[i_][j_] > 9 && !has_flashed.contains(&(i_, j_)) {
-                    queue.push((i_, j_));
-                }
+        for n in grid.neighbours_8(pos) {
+            grid[n] += 1;
+            if grid[n] > 9 && !has_flashed.contains(&n) {
+                queue.push(n);
             }
         }
-
-        num_flashes += has_flashed.len();
-        for (i, j) in has_flashed {
-            rows[i][j] = 0;
-        }
     }
 
+    let num_flashes = has_flashed.len();
+    for pos in has_flashed {
+        grid[pos] = 0;
+    }
     num_flashes
 }
 
-pub fn part_2(s: &str) -> usize {
-    let mut rows: Vec<Vec<u8>> = vec![];
-    for line in s.lines() {
-        let mut row = vec![];
-        for c in line.chars() {
-            row.push(c.to_digit(10).unwrap() as u8);
-        }
-        rows.push(row);
+fn count_flashes(grid: &Grid<u8>, steps: usize) -> usize {
+    let mut grid = grid.clone();
+    (0..steps).map(|_| step(&mut grid)).sum()
+}
+
+fn first_synchronized_step(grid: &Grid<u8>) -> usize {
+    let mut grid = grid.clone();
+
+    let mut step_idx = 1;
+    while step(&mut grid) != grid.len() {
+        step_idx += 1;
     }
+    step_idx
+}
 
-    let mut step = 1;
-    loop {
-        // Begining of step
-        let mut queue = vec![];
-        for i in 0..rows.len() {
-            for j in 0..rows[0].len() {
-                rows[i][j] += 1;
-                if rows[i][j] > 9 {
-                    queue.push((i, j));
-                }
-            }
-        }
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(count_flashes(&parse(s)?, 100))
+}
 
-        let mut has_flashed = HashSet::new();
-        while let Some((i, j)) = queue.pop() {
-            if has_flashed.contains(&(i, j)) {
-                continue;
-            }
-            assert!(has_flashed.insert((i, j)));
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(first_synchronized_step(&parse(s)?))
+}
 
-            for (i_, j_) in adjacent_points(i, j, rows.len(), rows[0].len()) {
-                rows[i_][j_] += 1;
-                if rows[i_][j_] > 9 && !has_flashed.contains(&(i_, j_)) {
-                    queue.push((i_, j_));
-                }
-            }
-        }
+pub struct Day11;
 
-        if has_flashed.len() == rows.len() * rows[0].len() {
-            break;
-        }
+impl Solution for Day11 {
+    const DAY: usize = 11;
 
-        for (i, j) in has_flashed {
-            rows[i][j] = 0;
-        }
+    type Input = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-        step += 1;
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
     }
 
-    step
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        count_flashes(input, 100)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(first_synchronized_step(input))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
 
     const EXAMPLE: &str = r#"5483143223
 2745854711
@@ -198,21 +165,27 @@ mod tests {
 
     #[test]
     fn test_day_11_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 1656);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 1656);
     }
 
     #[test]
     fn test_day_11_part_1() {
-        assert_eq!(part_1(include_str!("input/day_11.txt")), 1655);
+        assert_eq!(part_1(include_str!("input/day_11.txt")).unwrap(), 1655);
     }
 
     #[test]
     fn test_day_11_example_part_2() {
-        assert_eq!(part_2(EXAMPLE), 195);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 195);
     }
 
     #[test]
     fn test_day_11_part_2() {
-        assert_eq!(part_2(include_str!("input/day_11.txt")), 337);
+        assert_eq!(part_2(include_str!("input/day_11.txt")).unwrap(), 337);
+    }
+
+    #[test]
+    fn test_day_11_parse_error() {
+        let e = parse("5483\n274").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "274"));
     }
 }
@@ -175,10 +175,30 @@
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
-pub fn part_1(s: &str) -> usize {
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+
+pub fn parse(s: &str) -> Result<Vec<(String, String)>, ParseError> {
+    parse::lines(s)
+        .map(|line| {
+            let (from, to) = line.split_once(line.text, "-")?;
+            for cave in [from, to] {
+                if cave.is_empty() {
+                    return Err(line.error(cave, ErrorKind::MissingField));
+                }
+                if let Some((i, c)) = cave.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+                    let at = &cave[i..i + c.len_utf8()];
+                    return Err(line.error(at, ErrorKind::UnexpectedChar));
+                }
+            }
+            Ok((from.to_owned(), to.to_owned()))
+        })
+        .collect()
+}
+
+fn count_paths(connections: &[(String, String)]) -> usize {
     let mut edges: HashMap<String, Vec<String>> = HashMap::new();
-    for line in s.lines() {
-        let (from, to) = line.split_once("-").unwrap();
+    for (from, to) in connections {
         edges
             .entry(from.to_owned())
             .or_default()
@@ -212,12 +232,12 @@ pub fn part_1(s: &str) -> usize {
     num_paths
 }
 
-pub fn part_2(s: &str) -> usize {
+fn count_paths_with_revisit(connections: &[(String, String)]) -> usize {
     let mut edges: HashMap<usize, Vec<usize>> = HashMap::new();
     let mut nodes: HashMap<String, usize> = HashMap::new();
     let mut capitalized = vec![];
-    for line in s.lines() {
-        let (from, to) = line.split_once("-").unwrap();
+    for (from, to) in connections {
+        let (from, to) = (from.as_str(), to.as_str());
         if !nodes.contains_key(from) {
             nodes.insert(from.to_string(), capitalized.len());
             capitalized.push(from.chars().next().unwrap().is_uppercase());
@@ -295,9 +315,40 @@ pub fn part_2(s: &str) -> usize {
     num_paths
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(count_paths(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(count_paths_with_revisit(&parse(s)?))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: usize = 12;
+
+    type Input = Vec<(String, String)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        count_paths(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(count_paths_with_revisit(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     const EXAMPLE: &str = r#"start-A
 start-b
@@ -337,23 +388,31 @@ start-RW"#;
 
     #[test]
     fn test_day_12_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 10);
-        assert_eq!(part_1(EXAMPLE2), 19);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 10);
+        assert_eq!(part_1(EXAMPLE2).unwrap(), 19);
     }
 
     #[test]
     fn test_day_12_part_1() {
-        assert_eq!(part_1(include_str!("input/day_12.txt")), 3230);
+        assert_eq!(part_1(include_str!("input/day_12.txt")).unwrap(), 3230);
     }
 
     #[test]
     fn test_day_12_example_part_2() {
-        assert_eq!(part_2(EXAMPLE2), 103);
-        assert_eq!(part_2(EXAMPLE3), 3509);
+        assert_eq!(part_2(EXAMPLE2).unwrap(), 103);
+        assert_eq!(part_2(EXAMPLE3).unwrap(), 3509);
     }
 
     #[test]
     fn test_day_12_part_2() {
-        assert_eq!(part_2(include_str!("input/day_12.txt")), 83475);
+        assert_eq!(part_2(include_str!("input/day_12.txt")).unwrap(), 83475);
+    }
+
+    #[test]
+    fn test_day_12_parse_error() {
+        let e = parse("start-A\nA_b").unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (2, 1, ErrorKind::MissingField));
+        let e = parse("start-A\nA-b2").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 4, "2"));
     }
 }
@@ -146,6 +146,9 @@
 use std::collections::HashSet;
 use std::io::{Cursor, Write};
 
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+
 pub fn print_dots<W: Write>(dots: &HashSet<(usize, usize)>, io: &mut W) {
     let min_x = *dots.iter().map(|(x, _)| x).min().unwrap();
     let max_x = *dots.iter().map(|(x, _)| x).max().unwrap();
@@ -166,31 +169,58 @@ pub fn print_dots<W: Write>(dots: &HashSet<(usize, usize)>, io: &mut W) {
     }
 }
 
-pub fn part_1(s: &str) -> usize {
-    let mut dots: HashSet<(usize, usize)> = HashSet::new();
-    let mut iter = s.lines();
-    for line in &mut iter {
-        if line.is_empty() {
-            break;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fold {
+    Left(usize),
+    Up(usize),
+}
+
+#[derive(Debug)]
+pub struct Manual {
+    pub dots: HashSet<(usize, usize)>,
+    pub folds: Vec<Fold>,
+}
+
+pub fn parse(s: &str) -> Result<Manual, ParseError> {
+    let mut lines = parse::lines(s);
+
+    let mut dots = HashSet::new();
+    let blank = loop {
+        let line = lines.expect()?;
+        if line.text.is_empty() {
+            break line.number;
         }
-        let (x, y) = line.split_once(",").unwrap();
-        dots.insert((x.parse().unwrap(), y.parse().unwrap()));
+        let (x, y) = line.split_once(line.text, ",")?;
+        dots.insert((line.number(x)?, line.number(y)?));
+    };
+
+    let mut folds = vec![];
+    for line in &mut lines {
+        let instruction = line
+            .text
+            .strip_prefix("fold along ")
+            .ok_or_else(|| line.error(line.text, ErrorKind::UnknownKeyword))?;
+        let (axis, value) = line.split_once(instruction, "=")?;
+        let value = line.number(value)?;
+        folds.push(match axis {
+            "x" => Fold::Left(value),
+            "y" => Fold::Up(value),
+            _ => return Err(line.error(axis, ErrorKind::UnknownKeyword)),
+        });
+    }
+    if folds.is_empty() {
+        return Err(ParseError::end_of_input(blank + 1));
     }
 
-    let first_fold = iter.next().unwrap();
-    let (axis, value) = first_fold
-        .split_whitespace()
-        .last()
-        .unwrap()
-        .split_once("=")
-        .unwrap();
-    let value: usize = value.parse().unwrap();
+    Ok(Manual { dots, folds })
+}
 
+fn fold(dots: &HashSet<(usize, usize)>, fold: Fold) -> HashSet<(usize, usize)> {
     let mut new_dots = HashSet::new();
-    match axis {
-        "x" => {
+    match fold {
+        Fold::Left(value) => {
             // fold the paper left
-            for (x, y) in dots {
+            for &(x, y) in dots {
                 if x > value {
                     new_dots.insert((value - (x - value), y));
                 } else {
@@ -198,9 +228,9 @@ pub fn part_1(s: &str) -> usize {
                 }
             }
         }
-        "y" => {
+        Fold::Up(value) => {
             // fold the paper up
-            for (x, y) in dots {
+            for &(x, y) in dots {
                 if y > value {
                     new_dots.insert((x, value - (y - value)));
                 } else {
@@ -208,65 +238,58 @@ pub fn part_1(s: &str) -> usize {
                 }
             }
         }
-        _ => unreachable!(),
     }
-    new_dots.len()
+    new_dots
 }
 
-pub fn part_2(s: &str) -> String {
-    let mut dots: HashSet<(usize, usize)> = HashSet::new();
-    let mut iter = s.lines();
-    for line in &mut iter {
-        if line.is_empty() {
-            break;
-        }
-        let (x, y) = line.split_once(",").unwrap();
-        dots.insert((x.parse().unwrap(), y.parse().unwrap()));
-    }
+fn dots_after_first_fold(manual: &Manual) -> usize {
+    fold(&manual.dots, manual.folds[0]).len()
+}
 
-    for fold in iter {
-        let (axis, value) = fold
-            .split_whitespace()
-            .last()
-            .unwrap()
-            .split_once("=")
-            .unwrap();
-        let value: usize = value.parse().unwrap();
-
-        let mut new_dots = HashSet::new();
-        match axis {
-            "x" => {
-                // fold the paper left
-                for (x, y) in dots {
-                    if x > value {
-                        new_dots.insert((value - (x - value), y));
-                    } else {
-                        new_dots.insert((x, y));
-                    }
-                }
-            }
-            "y" => {
-                // fold the paper up
-                for (x, y) in dots {
-                    if y > value {
-                        new_dots.insert((x, value - (y - value)));
-                    } else {
-                        new_dots.insert((x, y));
-                    }
-                }
-            }
-            _ => unreachable!(),
-        }
-        dots = new_dots;
+fn code(manual: &Manual) -> String {
+    let mut dots = manual.dots.clone();
+    for f in &manual.folds {
+        dots = fold(&dots, *f);
     }
     let mut s = Vec::new();
     print_dots(&dots, &mut Cursor::new(&mut s));
     String::from_utf8(s).unwrap()
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(dots_after_first_fold(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<String, ParseError> {
+    Ok(code(&parse(s)?))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: usize = 13;
+
+    type Input = Manual;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        dots_after_first_fold(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(code(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     const EXAMPLE: &str = r#"6,10
 0,14
@@ -292,17 +315,17 @@ fold along x=5"#;
 
     #[test]
     fn test_day_13_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 17);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 17);
     }
 
     #[test]
     fn test_day_13_part_1() {
-        assert_eq!(part_1(include_str!("input/day_13.txt")), 942);
+        assert_eq!(part_1(include_str!("input/day_13.txt")).unwrap(), 942);
     }
 
     #[test]
     fn test_day_13_example_part_2() {
-        let answer = part_2(EXAMPLE);
+        let answer = part_2(EXAMPLE).unwrap();
         assert_eq!(
             answer,
             r#"
@@ -318,7 +341,7 @@ fold along x=5"#;
 
     #[test]
     fn test_day_13_part_2() {
-        let answer = part_2(include_str!("input/day_13.txt"));
+        let answer = part_2(include_str!("input/day_13.txt")).unwrap();
         assert_eq!(
             answer,
             r#"  ## ####  ##  #  #  ##  ###  ###  ### 
@@ -330,4 +353,15 @@ fold along x=5"#;
                 .to_string()
         );
     }
+
+    #[test]
+    fn test_day_13_parse_error() {
+        let e = parse("6,10\n0,14\n\nfold along z=7").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (4, 12, "z"));
+        let e = parse("6,10\n0,14\n").unwrap_err();
+        assert_eq!((e.line, e.kind), (3, ErrorKind::UnexpectedEnd));
+        // no folds after the blank line
+        let e = parse("6,10\n\n").unwrap_err();
+        assert_eq!((e.line, e.kind), (3, ErrorKind::UnexpectedEnd));
+    }
 }
@@ -91,31 +91,75 @@
 // common element?
 
 use std::collections::HashMap;
+use std::io::BufRead;
 
-pub fn part_1(s: &str) -> usize {
-    let mut iter = s.lines();
-    let mut template = iter.next().unwrap().chars().collect::<Vec<_>>();
-    iter.next().unwrap();
-
-    let replacements = iter
-        .map(|s| {
-            let (a, b) = s.split_once("->").unwrap();
-            let mut a_ = a.trim().chars();
-            let aa = a_.next().unwrap();
-            let ab = a_.next().unwrap();
-            let b = b.trim().chars().next().unwrap();
-            (aa, ab, b)
-        })
-        .collect::<Vec<_>>();
-
-    for _ in 0..10 {
+use crate::parse::{self, ErrorKind, Line, LineReader, ParseError, ReadError};
+use crate::solution::Solution;
+
+#[derive(Debug, PartialEq)]
+pub struct Polymer {
+    pub template: Vec<char>,
+    pub rules: Vec<(char, char, char)>,
+}
+
+fn parse_template(line: &Line<'_>) -> Result<Vec<char>, ParseError> {
+    if line.text.is_empty() {
+        return Err(line.missing());
+    }
+    Ok(line.text.chars().collect())
+}
+
+fn parse_rule(line: &Line<'_>) -> Result<(char, char, char), ParseError> {
+    let (pair, insert) = line.split_once(line.text, "->")?;
+    let (pair, insert) = (pair.trim(), insert.trim());
+    let mut chars = pair.chars();
+    let (a, b, c) = match (chars.next(), chars.next(), chars.next()) {
+        (Some(a), Some(b), None) => (a, b, insert.chars().next()),
+        _ => return Err(line.error(pair, ErrorKind::Invalid("expected a pair"))),
+    };
+    match (c, insert.len()) {
+        (Some(c), 1) => Ok((a, b, c)),
+        _ => Err(line.error(insert, ErrorKind::Invalid("expected one element"))),
+    }
+}
+
+pub fn parse(s: &str) -> Result<Polymer, ParseError> {
+    let mut lines = parse::lines(s);
+    let template = parse_template(&lines.expect()?)?;
+    lines.blank()?;
+    let rules = lines
+        .map(|line| parse_rule(&line))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Polymer { template, rules })
+}
+
+// Like `parse`, but loads the rules a line at a time from `r`.
+pub fn read(r: impl BufRead) -> Result<Polymer, ReadError> {
+    let mut lines = LineReader::new(r);
+    let template = parse_template(&lines.expect()?)?;
+    lines.expect()?.blank()?;
+
+    let mut rules = vec![];
+    while let Some(line) = lines.next_line()? {
+        rules.push(parse_rule(&line)?);
+    }
+
+    Ok(Polymer { template, rules })
+}
+
+fn expand_naive(polymer: &Polymer, steps: usize) -> usize {
+    let mut template = polymer.template.clone();
+    let replacements = &polymer.rules;
+
+    for _ in 0..steps {
         let mut rewritten = vec![];
         let mut templ_iter = template.iter();
         let mut templ_iter_2 = template.iter();
         rewritten.push(*templ_iter_2.next().unwrap());
 
         while let (Some(aa_), Some(ab_)) = (templ_iter.next(), templ_iter_2.next()) {
-            for (aa, ab, c) in &replacements {
+            for (aa, ab, c) in replacements {
                 if aa_ == aa && ab_ == ab {
                     rewritten.push(*c);
                 }
@@ -136,21 +180,10 @@ pub fn part_1(s: &str) -> usize {
     *max - *min
 }
 
-pub fn part_2(s: &str) -> usize {
-    let mut iter = s.lines();
-    let template = iter.next().unwrap().chars().collect::<Vec<_>>();
-    iter.next().unwrap();
-
-    let replacements = iter.map(|s| {
-        let (a, b) = s.split_once("->").unwrap();
-        let mut a_ = a.trim().chars();
-        let aa = a_.next().unwrap();
-        let ab = a_.next().unwrap();
-        let b = b.trim().chars().next().unwrap();
-        (aa, ab, b)
-    });
+fn expand_counts(polymer: &Polymer, steps: usize) -> usize {
+    let template = &polymer.template;
     let mut indexed_replacements: HashMap<(char, char), char> = HashMap::new();
-    for (a, b, c) in replacements {
+    for &(a, b, c) in &polymer.rules {
         indexed_replacements.insert((a, b), c);
     }
 
@@ -160,7 +193,7 @@ pub fn part_2(s: &str) -> usize {
     let mut iter = template.iter().copied();
     let mut iter_ = template.iter().skip(1).copied();
 
-    for c in &template {
+    for c in template {
         *char_counts.entry(*c).or_default() += 1;
     }
 
@@ -168,7 +201,7 @@ pub fn part_2(s: &str) -> usize {
         *pair_char_counts.entry((a, b)).or_default() += 1;
     }
 
-    for _ in 0..40 {
+    for _ in 0..steps {
         let mut pair_char_counts_ = HashMap::new();
         for ((a, b), ct) in pair_char_counts {
             if let Some(c) = indexed_replacements.get(&(a, b)) {
@@ -188,9 +221,47 @@ pub fn part_2(s: &str) -> usize {
     max - min
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(expand_naive(&parse(s)?, 10))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(expand_counts(&parse(s)?, 40))
+}
+
+pub fn read_part_1(r: impl BufRead) -> Result<usize, ReadError> {
+    Ok(expand_naive(&read(r)?, 10))
+}
+
+pub fn read_part_2(r: impl BufRead) -> Result<usize, ReadError> {
+    Ok(expand_counts(&read(r)?, 40))
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: usize = 14;
+
+    type Input = Polymer;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        expand_naive(input, 10)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(expand_counts(input, 40))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2, read, read_part_1, read_part_2};
 
     const EXAMPLE: &str = r#"NNCB
 
@@ -213,23 +284,40 @@ CN -> C"#;
 
     #[test]
     fn test_day_14_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 1588);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 1588);
     }
 
     #[test]
     fn test_day_14_part_1() {
-        assert_eq!(part_1(include_str!("input/day_14.txt")), 2194);
+        assert_eq!(part_1(include_str!("input/day_14.txt")).unwrap(), 2194);
     }
 
     #[test]
     fn test_day_14_example_part_2() {
-        let answer = part_2(EXAMPLE);
+        let answer = part_2(EXAMPLE).unwrap();
         assert_eq!(answer, 2188189693529);
     }
 
     #[test]
     fn test_day_14_part_2() {
-        let answer = part_2(include_str!("input/day_14.txt"));
+        let answer = part_2(include_str!("input/day_14.txt")).unwrap();
         assert_eq!(answer, 2360298895777);
     }
+
+    #[test]
+    fn test_day_14_read() {
+        assert_eq!(read(EXAMPLE.as_bytes()).unwrap(), parse(EXAMPLE).unwrap());
+        assert_eq!(read_part_1(EXAMPLE.as_bytes()).unwrap(), 1588);
+        let input = include_str!("input/day_14.txt");
+        assert_eq!(read_part_2(input.as_bytes()).unwrap(), 2360298895777);
+        assert!(read("NNCB\nCH -> B".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_day_14_parse_error() {
+        let e = parse("NNCB\n\nCH -> B\nHHH -> N").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (4, 1, "HHH"));
+        let e = parse("NNCB\n\nCH -> B\nHH ->").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (4, 6, ""));
+    }
 }
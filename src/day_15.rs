@@ -85,39 +85,17 @@
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
 
-fn adjacent_points(
-    i: usize,
-    j: usize,
-    max_i: usize,
-    max_j: usize,
-) -> impl Iterator<Item = (usize, usize)> {
-    (-1isize..=1)
-        .flat_map(move |i_off| {
-            (-1isize..=1).map(move |j_off| (i as isize + i_off, j as isize + j_off))
-        })
-        .filter(move |(i_, j_)| {
-            *i_ >= 0
-                && *i_ < max_i as isize
-                && *j_ >= 0
-                && *j_ < max_j as isize
-                && (i as isize, j as isize) != (*i_, *j_)
-        })
-        .map(|(i_, j_)| (i_ as usize, j_ as usize))
-        .filter(move |(i_, j_)| *i_ == i || *j_ == j)
-}
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
-pub fn part_1(s: &str) -> usize {
-    let mut rows: Vec<Vec<u8>> = vec![];
-    for line in s.lines() {
-        let mut row = vec![];
-        for c in line.chars() {
-            row.push(c.to_digit(10).unwrap() as u8);
-        }
-        rows.push(row);
-    }
+pub fn parse(s: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse_digits(s)
+}
 
+fn lowest_total_risk(grid: &Grid<u8>) -> usize {
     let start = (0, 0);
-    let end = (rows.len() - 1, rows[0].len() - 1);
+    let end = (grid.height() - 1, grid.width() - 1);
 
     let mut dist = HashMap::new();
 
@@ -141,10 +119,8 @@ pub fn part_1(s: &str) -> usize {
         }
     }
 
-    for i in 0..rows.len() {
-        for j in 0..rows[0].len() {
-            dist.insert((i, j), std::usize::MAX);
-        }
+    for pos in grid.positions() {
+        dist.insert(pos, usize::MAX);
     }
     dist.insert(start, 0);
     let mut heap = BinaryHeap::new();
@@ -161,12 +137,10 @@ pub fn part_1(s: &str) -> usize {
         if cost > dist[&position] {
             continue;
         }
-        let (i, j) = position;
-
-        for (i_, j_) in adjacent_points(i, j, rows.len(), rows[0].len()) {
+        for n in grid.neighbours_4(position) {
             let next = State {
-                cost: cost + rows[i_][j_] as usize,
-                position: (i_, j_),
+                cost: cost + grid[n] as usize,
+                position: n,
             };
 
             if next.cost < dist[&next.position] {
@@ -179,32 +153,51 @@ pub fn part_1(s: &str) -> usize {
     unreachable!()
 }
 
-pub fn part_2(s: &str) -> usize {
-    // repeat s 5 times by 5 times
-    let mut s_ = String::new();
-
-    for i in 0..5 {
-        for line in s.lines() {
-            for j in 0..5 {
-                for c in line.chars() {
-                    let mut v = c.to_digit(10).unwrap() + i + j;
-                    while v > 9 {
-                        v -= 9;
-                    }
-
-                    s_ += &(v.to_string());
-                }
-            }
-            s_ += "\n";
+fn expand(grid: &Grid<u8>) -> Grid<u8> {
+    // repeat the map 5 times by 5 times
+    let (w, h) = (grid.width(), grid.height());
+    Grid::from_fn(w * 5, h * 5, |(i, j)| {
+        let mut v = grid[(i % h, j % w)] + (i / h + j / w) as u8;
+        while v > 9 {
+            v -= 9;
         }
+        v
+    })
+}
+
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(lowest_total_risk(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(lowest_total_risk(&expand(&parse(s)?)))
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: usize = 15;
+
+    type Input = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
     }
 
-    part_1(&s_)
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        lowest_total_risk(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(lowest_total_risk(&expand(input)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
 
     const EXAMPLE: &str = r#"1163751742
 1381373672
@@ -219,23 +212,29 @@ mod tests {
 
     #[test]
     fn test_day_15_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 40);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 40);
     }
 
     #[test]
     fn test_day_15_part_1() {
-        assert_eq!(part_1(include_str!("input/day_15.txt")), 595);
+        assert_eq!(part_1(include_str!("input/day_15.txt")).unwrap(), 595);
     }
 
     #[test]
     fn test_day_15_example_part_2() {
-        let answer = part_2(EXAMPLE);
+        let answer = part_2(EXAMPLE).unwrap();
         assert_eq!(answer, 315);
     }
 
     #[test]
     fn test_day_15_part_2() {
-        let answer = part_2(include_str!("input/day_15.txt"));
+        let answer = part_2(include_str!("input/day_15.txt")).unwrap();
         assert_eq!(answer, 2914);
     }
+
+    #[test]
+    fn test_day_15_parse_error() {
+        let e = parse("1163\n13-8").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 3, "-"));
+    }
 }
@@ -204,6 +204,9 @@
 // What do you get if you evaluate the expression represented by your
 // hexadecimal-encoded BITS transmission?
 
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+
 fn parse_packet_header(iter: &mut impl Iterator<Item = usize>) -> Option<(usize, usize)> {
     let version = iter.next()? << 2 | iter.next()? << 1 | iter.next()?;
     let typ = iter.next()? << 2 | iter.next()? << 1 | iter.next()?;
@@ -218,20 +221,20 @@ fn parse_packet(iter: &mut impl Iterator<Item = usize>) -> Option<(usize, usize)
         let mut done = false;
         let mut v = 0;
         while !done {
-            done = iter.next().unwrap() == 0;
+            done = iter.next()? == 0;
             // read 4 bits
             for _ in 0..4 {
-                v = v << 1 | iter.next().unwrap();
+                v = v << 1 | iter.next()?;
             }
         }
         Some((version_sum, v))
     } else {
         let mut operands = vec![];
-        match iter.next().unwrap() {
+        match iter.next()? {
             0 => {
                 let mut num_bits = 0;
                 for _ in 0..15 {
-                    num_bits = num_bits << 1 | iter.next().unwrap();
+                    num_bits = num_bits << 1 | iter.next()?;
                 }
                 let mut sub = Box::new(iter.take(num_bits)) as Box<dyn Iterator<Item = _>>;
                 while let Some((v, vv)) = parse_packet(&mut sub) {
@@ -242,11 +245,11 @@ fn parse_packet(iter: &mut impl Iterator<Item = usize>) -> Option<(usize, usize)
             1 => {
                 let mut num_packet = 0;
                 for _ in 0..11 {
-                    num_packet = num_packet << 1 | iter.next().unwrap();
+                    num_packet = num_packet << 1 | iter.next()?;
                 }
 
                 for _ in 0..num_packet {
-                    let (v, vv) = parse_packet(iter).unwrap();
+                    let (v, vv) = parse_packet(iter)?;
                     version_sum += v;
                     operands.push(vv);
                 }
@@ -256,52 +259,86 @@ fn parse_packet(iter: &mut impl Iterator<Item = usize>) -> Option<(usize, usize)
         let out = match typ {
             0 => operands.into_iter().sum::<usize>(),
             1 => operands.into_iter().product::<usize>(),
-            2 => operands.into_iter().min().unwrap(),
-            3 => operands.into_iter().max().unwrap(),
+            2 => operands.into_iter().min()?,
+            3 => operands.into_iter().max()?,
+            5..=7 if operands.len() != 2 => return None,
             5 if operands[0] > operands[1] => 1,
             5 => 0,
             6 if operands[0] < operands[1] => 1,
             6 => 0,
             7 if operands[0] == operands[1] => 1,
             7 => 0,
-            _ => unreachable!(),
+            _ => return None,
         };
         Some((version_sum, out))
     }
 }
 
-pub fn part_1(s: &str) -> usize {
-    let mut iter = s.trim().chars().flat_map(|c| {
-        let i = c.to_digit(16).unwrap() as usize;
-        [
+pub fn parse(s: &str) -> Result<Vec<usize>, ParseError> {
+    let line = parse::lines(s).expect()?;
+    let text = line.text.trim_end();
+    let mut bits = vec![];
+    for (c, at) in line.chars().take(text.len()) {
+        let i = line.digit(c, at, 16)? as usize;
+        bits.extend([
             (i & (1 << 3) != 0) as usize,
             (i & (1 << 2) != 0) as usize,
             (i & (1 << 1) != 0) as usize,
             (i & 1 != 0) as usize,
-        ]
-    });
+        ]);
+    }
 
-    let (v, _) = parse_packet(&mut iter).unwrap();
+    // decode once up front so the parts can rely on a well-formed packet
+    if parse_packet(&mut bits.iter().copied()).is_none() {
+        return Err(line.error(text, ErrorKind::Invalid("malformed packet")));
+    }
+    Ok(bits)
+}
+
+fn version_sum(bits: &[usize]) -> usize {
+    let (v, _) = parse_packet(&mut bits.iter().copied()).unwrap();
     v
 }
 
-pub fn part_2(s: &str) -> usize {
-    let mut iter = s.trim().chars().flat_map(|c| {
-        let i = c.to_digit(16).unwrap() as usize;
-        [
-            (i & (1 << 3) != 0) as usize,
-            (i & (1 << 2) != 0) as usize,
-            (i & (1 << 1) != 0) as usize,
-            (i & 1 != 0) as usize,
-        ]
-    });
-    let (_, v) = parse_packet(&mut iter).unwrap();
+fn evaluate(bits: &[usize]) -> usize {
+    let (_, v) = parse_packet(&mut bits.iter().copied()).unwrap();
     v
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(version_sum(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(evaluate(&parse(s)?))
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: usize = 16;
+
+    type Input = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        version_sum(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(evaluate(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     const EXAMPLE: &str = r#"8A004A801A8002F478"#;
     const EXAMPLE2: &str = r#"620080001611562C8802118E34"#;
@@ -310,32 +347,41 @@ mod tests {
 
     #[test]
     fn test_day_16_example_part_1() {
-        assert_eq!(part_1("D2FE28"), 6);
-        assert_eq!(part_1(EXAMPLE), 16);
-        assert_eq!(part_1(EXAMPLE2), 12);
-        assert_eq!(part_1(EXAMPLE3), 23);
-        assert_eq!(part_1(EXAMPLE4), 31);
+        assert_eq!(part_1("D2FE28").unwrap(), 6);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 16);
+        assert_eq!(part_1(EXAMPLE2).unwrap(), 12);
+        assert_eq!(part_1(EXAMPLE3).unwrap(), 23);
+        assert_eq!(part_1(EXAMPLE4).unwrap(), 31);
     }
 
     #[test]
     fn test_day_16_part_1() {
-        assert_eq!(part_1(include_str!("input/day_16.txt")), 843);
+        assert_eq!(part_1(include_str!("input/day_16.txt")).unwrap(), 843);
     }
 
     #[test]
     fn test_day_16_example_part_2() {
-        assert_eq!(part_2("C200B40A82"), 3);
-        assert_eq!(part_2("04005AC33890"), 54);
-        assert_eq!(part_2("880086C3E88112"), 7);
-        assert_eq!(part_2("CE00C43D881120"), 9);
-        assert_eq!(part_2("D8005AC2A8F0"), 1);
-        assert_eq!(part_2("9C005AC2F8F0"), 0);
-        assert_eq!(part_2("9C0141080250320F1802104A08"), 1);
+        assert_eq!(part_2("C200B40A82").unwrap(), 3);
+        assert_eq!(part_2("04005AC33890").unwrap(), 54);
+        assert_eq!(part_2("880086C3E88112").unwrap(), 7);
+        assert_eq!(part_2("CE00C43D881120").unwrap(), 9);
+        assert_eq!(part_2("D8005AC2A8F0").unwrap(), 1);
+        assert_eq!(part_2("9C005AC2F8F0").unwrap(), 0);
+        assert_eq!(part_2("9C0141080250320F1802104A08").unwrap(), 1);
     }
 
     #[test]
     fn test_day_16_part_2() {
-        let answer = part_2(include_str!("input/day_16.txt"));
+        let answer = part_2(include_str!("input/day_16.txt")).unwrap();
         assert_eq!(answer, 5390807940351);
     }
+
+    #[test]
+    fn test_day_16_parse_error() {
+        let e = parse("D2FG28").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 4, "G"));
+        let e = parse("D2FE").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Invalid("malformed packet"));
+        assert!(parse("").is_err());
+    }
 }
@@ -167,6 +167,9 @@
 
 use std::collections::HashSet;
 
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+
 fn simulate(
     mut dx: isize,
     mut dy: isize,
@@ -202,25 +205,52 @@ fn simulate(
     }
 }
 
-pub fn part_1(s: &str) -> isize {
-    let mut min_x = 0;
-    let mut max_x = 0;
-    let mut min_y = 0;
-    let mut max_y = 0;
-    for v in s.split_whitespace() {
-        if v.starts_with("x=") {
-            let (_, r) = v.split_once("=").unwrap();
-            let (min_x_, max_x_) = r.split_once("..").unwrap();
-            min_x = min_x_.parse().unwrap();
-            max_x = max_x_.trim_end_matches(',').parse().unwrap();
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Target {
+    pub min_x: isize,
+    pub max_x: isize,
+    pub min_y: isize,
+    pub max_y: isize,
+}
+
+pub fn parse(s: &str) -> Result<Target, ParseError> {
+    let line = parse::lines(s).expect()?;
+    let area = line
+        .text
+        .strip_prefix("target area: ")
+        .ok_or_else(|| line.error(line.text, ErrorKind::UnknownKeyword))?;
+    let (x, y) = line.split_once(area.trim_end(), ", ")?;
+
+    let range = |v: &'_ str, axis: &str| {
+        let (name, r) = line.split_once(v, "=")?;
+        if name != axis {
+            return Err(line.error(name, ErrorKind::UnknownKeyword));
         }
-        if v.starts_with("y=") {
-            let (_, r) = v.split_once("=").unwrap();
-            let (min_y_, max_y_) = r.split_once("..").unwrap();
-            min_y = min_y_.parse().unwrap();
-            max_y = max_y_.parse().unwrap();
+        let (lo, hi) = line.split_once(r, "..")?;
+        let (lo, hi) = (line.number(lo)?, line.number(hi)?);
+        if lo > hi {
+            return Err(line.error(r, ErrorKind::Invalid("range is backwards")));
         }
-    }
+        Ok((lo, hi))
+    };
+    let (min_x, max_x) = range(x, "x")?;
+    let (min_y, max_y) = range(y, "y")?;
+
+    Ok(Target {
+        min_x,
+        max_x,
+        min_y,
+        max_y,
+    })
+}
+
+fn highest_peak(target: &Target) -> isize {
+    let Target {
+        min_x,
+        max_x,
+        min_y,
+        max_y,
+    } = *target;
 
     // the highest possible y value must be positive
     let mut peak = 0;
@@ -235,25 +265,13 @@ pub fn part_1(s: &str) -> isize {
     peak
 }
 
-pub fn part_2(s: &str) -> isize {
-    let mut min_x = 0;
-    let mut max_x = 0;
-    let mut min_y = 0;
-    let mut max_y = 0;
-    for v in s.split_whitespace() {
-        if v.starts_with("x=") {
-            let (_, r) = v.split_once("=").unwrap();
-            let (min_x_, max_x_) = r.split_once("..").unwrap();
-            min_x = min_x_.parse().unwrap();
-            max_x = max_x_.trim_end_matches(',').parse().unwrap();
-        }
-        if v.starts_with("y=") {
-            let (_, r) = v.split_once("=").unwrap();
-            let (min_y_, max_y_) = r.split_once("..").unwrap();
-            min_y = min_y_.parse().unwrap();
-            max_y = max_y_.parse().unwrap();
-        }
-    }
+fn count_velocities(target: &Target) -> isize {
+    let Target {
+        min_x,
+        max_x,
+        min_y,
+        max_y,
+    } = *target;
 
     let mut found = HashSet::new();
     for dy in -100..100 {
@@ -267,30 +285,69 @@ pub fn part_2(s: &str) -> isize {
     found.len() as isize
 }
 
+pub fn part_1(s: &str) -> Result<isize, ParseError> {
+    Ok(highest_peak(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<isize, ParseError> {
+    Ok(count_velocities(&parse(s)?))
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: usize = 17;
+
+    type Input = Target;
+    type Answer1 = isize;
+    type Answer2 = isize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        highest_peak(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(count_velocities(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     const EXAMPLE: &str = r#"target area: x=20..30, y=-10..-5"#;
 
     #[test]
     fn test_day_17_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 45);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 45);
     }
 
     #[test]
     fn test_day_17_part_1() {
-        assert_eq!(part_1(include_str!("input/day_17.txt")), 4005);
+        assert_eq!(part_1(include_str!("input/day_17.txt")).unwrap(), 4005);
     }
 
     #[test]
     fn test_day_17_example_part_2() {
-        assert_eq!(part_2(EXAMPLE), 112);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 112);
     }
 
     #[test]
     fn test_day_17_part_2() {
-        let answer = part_2(include_str!("input/day_17.txt"));
+        let answer = part_2(include_str!("input/day_17.txt")).unwrap();
         assert_eq!(answer, 2953);
     }
+
+    #[test]
+    fn test_day_17_parse_error() {
+        let e = parse("target area: x=20..30, y=-10..-5x").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 31, "-5x"));
+        let e = parse("target area: x=20..30, z=-10..-5").unwrap_err();
+        assert_eq!((e.column, e.kind), (24, ErrorKind::UnknownKeyword));
+    }
 }
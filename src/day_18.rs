@@ -1,3 +1,8 @@
+use std::iter::Peekable;
+
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum V {
     Open,
@@ -6,36 +11,68 @@ pub enum V {
     Close,
 }
 
-fn parse_val(s: impl IntoIterator<Item = char>) -> Vec<V> {
-    let mut v = vec![];
-    let mut digits = None;
-    for c in s {
-        match c {
-            '[' => {
-                v.push(V::Open);
-            }
-            '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
-                if digits.is_none() {
-                    digits = Some(String::new());
-                }
-                digits.as_mut().unwrap().push(c);
-            }
-            ',' => {
-                if let Some(p) = digits.take() {
-                    v.push(V::Literal(p.parse().unwrap()));
-                }
-                v.push(V::Delim);
-            }
-            ']' => {
-                if let Some(p) = digits.take() {
-                    v.push(V::Literal(p.parse().unwrap()));
-                }
-                v.push(V::Close);
+fn expect<'a>(
+    line: &parse::Line<'a>,
+    chars: &mut impl Iterator<Item = (char, &'a str)>,
+    want: char,
+) -> Result<(), ParseError> {
+    match chars.next() {
+        Some((c, _)) if c == want => Ok(()),
+        Some((_, at)) => Err(line.error(at, ErrorKind::UnexpectedChar)),
+        None => Err(line.missing()),
+    }
+}
+
+fn parse_elem<'a>(
+    line: &parse::Line<'a>,
+    chars: &mut Peekable<impl Iterator<Item = (char, &'a str)>>,
+    v: &mut Vec<V>,
+) -> Result<(), ParseError> {
+    match chars.peek().copied() {
+        Some(('[', _)) => {
+            chars.next();
+            v.push(V::Open);
+            parse_elem(line, chars, v)?;
+            expect(line, chars, ',')?;
+            v.push(V::Delim);
+            parse_elem(line, chars, v)?;
+            expect(line, chars, ']')?;
+            v.push(V::Close);
+        }
+        Some((c, at)) if c.is_ascii_digit() => {
+            let mut x: usize = 0;
+            while let Some(d) = chars.peek().and_then(|(c, _)| c.to_digit(10)) {
+                chars.next();
+                x = x
+                    .checked_mul(10)
+                    .and_then(|x| x.checked_add(d as usize))
+                    .ok_or_else(|| line.error(at, ErrorKind::InvalidNumber))?;
             }
-            _ => unreachable!(),
+            v.push(V::Literal(x));
         }
+        Some((_, at)) => return Err(line.error(at, ErrorKind::UnexpectedChar)),
+        None => return Err(line.missing()),
     }
-    v
+    Ok(())
+}
+
+fn parse_val(line: &parse::Line<'_>) -> Result<Vec<V>, ParseError> {
+    let mut v = vec![];
+    let mut chars = line.chars().peekable();
+    parse_elem(line, &mut chars, &mut v)?;
+    if let Some((_, at)) = chars.next() {
+        return Err(line.error(at, ErrorKind::UnexpectedChar));
+    }
+    Ok(v)
+}
+
+pub fn parse(s: &str) -> Result<Vec<Vec<V>>, ParseError> {
+    let mut lines = parse::lines(s);
+    let first = lines.expect()?;
+    std::iter::once(first)
+        .chain(lines)
+        .map(|line| parse_val(&line))
+        .collect()
 }
 
 pub fn print_val(v: &[V]) {
@@ -88,7 +125,7 @@ fn reduce(mut v: Vec<V>) -> (Vec<V>, bool) {
     match action {
         Some(Action::Split { at, val }) => {
             let lhs = val / 2;
-            let rhs = (val + 1) / 2;
+            let rhs = val.div_ceil(2);
             v[at] = V::Open;
             v.insert(at + 1, V::Literal(lhs));
             v.insert(at + 2, V::Delim);
@@ -144,7 +181,7 @@ fn magnitude(v: &[V]) -> (usize, usize) {
         V::Literal(x) => (x, 1),
         V::Open => {
             let (lhs, len) = magnitude(&v[1..]);
-            let (rhs, len_) = magnitude(&v[(2 + len..)]);
+            let (rhs, len_) = magnitude(&v[2 + len..]);
             // lhs len + rhs len + [,]
             (lhs * 3 + rhs * 2, len_ + len + 3)
         }
@@ -152,13 +189,13 @@ fn magnitude(v: &[V]) -> (usize, usize) {
     }
 }
 
-pub fn part_1(s: &str) -> usize {
-    let mut iter = s.lines();
+fn sum_magnitude(numbers: &[Vec<V>]) -> usize {
+    let mut iter = numbers.iter();
     let first = iter.next().unwrap();
-    let mut v = reduced(parse_val(first.chars()));
+    let mut v = reduced(first.clone());
 
     for next in iter {
-        let v2 = reduced(parse_val(next.chars()));
+        let v2 = reduced(next.clone());
         v.insert(0, V::Open);
         v.push(V::Delim);
         v.extend(v2);
@@ -169,14 +206,14 @@ pub fn part_1(s: &str) -> usize {
     magnitude(&v).0
 }
 
-pub fn part_2(s: &str) -> usize {
+fn largest_pair_magnitude(numbers: &[Vec<V>]) -> usize {
     let mut max_magnitude = 0;
 
-    for (idx, line) in s.lines().enumerate() {
-        let v = reduced(parse_val(line.chars()));
-        for (idx2, line2) in s.lines().enumerate() {
+    for (idx, line) in numbers.iter().enumerate() {
+        let v = reduced(line.clone());
+        for (idx2, line2) in numbers.iter().enumerate() {
             if idx != idx2 {
-                let v2 = reduced(parse_val(line2.chars()));
+                let v2 = reduced(line2.clone());
                 let mut v3 = vec![V::Open];
                 v3.extend(v.clone());
                 v3.push(V::Delim);
@@ -190,9 +227,40 @@ pub fn part_2(s: &str) -> usize {
     max_magnitude
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(sum_magnitude(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(largest_pair_magnitude(&parse(s)?))
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: usize = 18;
+
+    type Input = Vec<Vec<V>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        sum_magnitude(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(largest_pair_magnitude(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     const EXAMPLE: &str = r#"[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
 [[[5,[2,8]],4],[5,[[9,9],0]]]
@@ -207,22 +275,32 @@ mod tests {
 
     #[test]
     fn test_day_18_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 4140);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 4140);
     }
 
     #[test]
     fn test_day_18_part_1() {
-        assert_eq!(part_1(include_str!("input/day_18.txt")), 2907);
+        assert_eq!(part_1(include_str!("input/day_18.txt")).unwrap(), 2907);
     }
 
     #[test]
     fn test_day_18_example_part_2() {
-        assert_eq!(part_2(EXAMPLE), 3993);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 3993);
     }
 
     #[test]
     fn test_day_18_part_2() {
-        let answer = part_2(include_str!("input/day_18.txt"));
+        let answer = part_2(include_str!("input/day_18.txt")).unwrap();
         assert_eq!(answer, 4690);
     }
+
+    #[test]
+    fn test_day_18_parse_error() {
+        let e = parse("[1,2]\n[[1,2],3;]").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 9, ";"));
+        let e = parse("[[1,2],[3,4]").unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (1, 13, ErrorKind::MissingField));
+        let e = parse("[1,2]]").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (6, "]"));
+    }
 }
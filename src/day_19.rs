@@ -419,6 +419,12 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::executor;
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+use crate::trace;
+use crate::trace::{Silent, Tracer};
+
 type Mat3 = [[isize; 3]; 3];
 type Vec3 = [isize; 3];
 
@@ -539,33 +545,50 @@ fn fingerprint(points: &[Vec3]) -> HashSet<isize> {
     distances
 }
 
-fn shared(s: &str) -> (HashMap<String, Arc<Vec<Vec3>>>, Vec<Vec3>) {
+pub fn parse(s: &str) -> Result<HashMap<String, Arc<Vec<Vec3>>>, ParseError> {
     let mut scanners = HashMap::new();
-    let mut iter = s.lines();
-    let mut cur_scanner: (String, Vec<Vec3>) = (iter.next().unwrap().to_owned(), vec![]);
-
-    let mut done_scanners = HashSet::new();
-    let mut remapped_scanners = HashMap::new();
+    let mut iter = parse::lines(s);
+    let first = iter.expect()?;
+    if !first.text.starts_with("---") {
+        return Err(first.error(first.text, ErrorKind::Invalid("expected a scanner header")));
+    }
+    let mut cur_scanner: (String, Vec<Vec3>) = (first.text.to_owned(), vec![]);
 
     for line in iter {
-        if line.is_empty() {
+        if line.text.is_empty() {
             continue;
-        } else if line.starts_with("---") {
+        } else if line.text.starts_with("---") {
             scanners.insert(cur_scanner.0, Arc::new(cur_scanner.1));
-            cur_scanner = (line.to_owned(), vec![]);
+            cur_scanner = (line.text.to_owned(), vec![]);
         } else {
-            let mut seq = line.split(',').map(|v| v.parse().unwrap());
+            let mut seq = line.text.split(',');
             cur_scanner.1.push([
-                seq.next().unwrap(),
-                seq.next().unwrap(),
-                seq.next().unwrap(),
+                line.number(line.field(&mut seq)?)?,
+                line.number(line.field(&mut seq)?)?,
+                line.number(line.field(&mut seq)?)?,
             ]);
+            if let Some(extra) = seq.next() {
+                return Err(line.error(extra, ErrorKind::UnexpectedChar));
+            }
         }
     }
     scanners.insert(cur_scanner.0, Arc::new(cur_scanner.1));
 
+    if !scanners.contains_key("--- scanner 0 ---") {
+        return Err(first.error(first.text, ErrorKind::Invalid("missing scanner 0")));
+    }
+    Ok(scanners)
+}
+
+fn shared(
+    scanners: &HashMap<String, Arc<Vec<Vec3>>>,
+    tracer: &mut dyn Tracer,
+) -> (HashMap<String, Arc<Vec<Vec3>>>, Vec<Vec3>) {
+    let mut done_scanners = HashSet::new();
+    let mut remapped_scanners = HashMap::new();
+
     let mut fingerprints = HashMap::new();
-    for (n, v) in &scanners {
+    for (n, v) in scanners {
         fingerprints.insert(n.to_owned(), fingerprint(v));
     }
 
@@ -595,12 +618,13 @@ fn shared(s: &str) -> (HashMap<String, Arc<Vec<Vec3>>>, Vec<Vec3>) {
         Arc::clone(&scanners["--- scanner 0 ---"]),
     );
 
-    let mut deltas = vec![];
+    // scanner 0 is the origin, so it counts as a scanner position too
+    let mut deltas = vec![[0, 0, 0]];
 
     while done_scanners.len() < scanners.len() {
         for scanner in &scanner_keys {
             if remapped_scanners.contains_key(scanner) && !done_scanners.contains(scanner) {
-                eprintln!("{}", scanner);
+                trace!(tracer, "{}", scanner);
 
                 let mut work = vec![];
                 for scanner2 in &viable_searches[scanner] {
@@ -610,30 +634,24 @@ fn shared(s: &str) -> (HashMap<String, Arc<Vec<Vec3>>>, Vec<Vec3>) {
                     }
                 }
 
-                // This is not the idiomatic way to parallelize things in Rust,
-                // but this is a no-libraries solution so no threadpool library
-                // and no mpmc. Using a condvar seems like overkill.
-                for group in work.chunks(8) {
-                    let mut threads = vec![];
-                    for (scanner, scanner2) in group {
-                        let a = Arc::clone(&remapped_scanners[*scanner]);
-                        let b = Arc::clone(&scanners[scanner2]);
-                        let scanner2 = scanner2.clone();
-
-                        threads.push(std::thread::spawn(move || {
-                            common_enough(&a, &b).map(|(a, b)| (scanner2, a, b))
-                        }));
-                    }
-                    for thread in threads {
-                        if let Some((scanner2, v, delta)) = thread.join().unwrap() {
-                            remapped_scanners.insert(scanner2.to_owned(), Arc::new(v));
-                            deltas.push(delta);
-                        }
-                    }
+                let work = work
+                    .into_iter()
+                    .map(|(scanner, scanner2)| {
+                        let a = Arc::clone(&remapped_scanners[scanner]);
+                        let b = Arc::clone(&scanners[&scanner2]);
+                        (scanner2, a, b)
+                    })
+                    .collect();
+                let found = executor::global().map(work, |(scanner2, a, b)| {
+                    common_enough(&a, &b).map(|(a, b)| (scanner2, a, b))
+                });
+                for (scanner2, v, delta) in found.into_iter().flatten() {
+                    remapped_scanners.insert(scanner2, Arc::new(v));
+                    deltas.push(delta);
                 }
 
                 done_scanners.insert(scanner);
-                eprintln!("done: {}/{}", done_scanners.len(), scanners.len());
+                trace!(tracer, "done: {}/{}", done_scanners.len(), scanners.len());
             }
         }
     }
@@ -641,8 +659,8 @@ fn shared(s: &str) -> (HashMap<String, Arc<Vec<Vec3>>>, Vec<Vec3>) {
     (remapped_scanners, deltas)
 }
 
-pub fn part_1(s: &str) -> usize {
-    let (remapped, _) = shared(s);
+fn count_beacons(scanners: &HashMap<String, Arc<Vec<Vec3>>>, tracer: &mut dyn Tracer) -> usize {
+    let (remapped, _) = shared(scanners, tracer);
     let mut all_points = HashSet::new();
 
     for points in remapped.values() {
@@ -652,8 +670,8 @@ pub fn part_1(s: &str) -> usize {
     all_points.len()
 }
 
-pub fn part_2(s: &str) -> isize {
-    let (_, deltas) = shared(s);
+fn largest_distance(scanners: &HashMap<String, Arc<Vec<Vec3>>>, tracer: &mut dyn Tracer) -> isize {
+    let (_, deltas) = shared(scanners, tracer);
 
     let mut max_dist = 0;
     for a in &deltas {
@@ -669,28 +687,67 @@ pub fn part_2(s: &str) -> isize {
     max_dist
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(count_beacons(&parse(s)?, &mut Silent))
+}
+
+pub fn part_2(s: &str) -> Result<isize, ParseError> {
+    Ok(largest_distance(&parse(s)?, &mut Silent))
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: usize = 19;
+
+    type Input = HashMap<String, Arc<Vec<Vec3>>>;
+    type Answer1 = usize;
+    type Answer2 = isize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        count_beacons(input, &mut Silent)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(largest_distance(input, &mut Silent))
+    }
+
+    fn part_1_traced(input: &Self::Input, tracer: &mut dyn Tracer) -> Self::Answer1 {
+        count_beacons(input, tracer)
+    }
+
+    fn part_2_traced(input: &Self::Input, tracer: &mut dyn Tracer) -> Option<Self::Answer2> {
+        Some(largest_distance(input, tracer))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     #[test]
     fn test_day_19_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 79);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 79);
     }
 
     #[test]
     fn test_day_19_part_1() {
-        assert_eq!(part_1(include_str!("input/day_19.txt")), 378);
+        assert_eq!(part_1(include_str!("input/day_19.txt")).unwrap(), 378);
     }
 
     #[test]
     fn test_day_19_example_part_2() {
-        assert_eq!(part_2(EXAMPLE), 3621);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 3621);
     }
 
     #[test]
     fn test_day_19_part_2() {
-        let answer = part_2(include_str!("input/day_19.txt"));
+        let answer = part_2(include_str!("input/day_19.txt")).unwrap();
         assert_eq!(answer, 13148);
     }
 
@@ -830,4 +887,12 @@ mod tests {
 891,-625,532
 -652,-548,-490
 30,-46,-14"#;
+
+    #[test]
+    fn test_day_19_parse_error() {
+        let e = parse("--- scanner 0 ---\n404,-588,-901\n528,-643\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (3, 9, ErrorKind::MissingField));
+        let e = parse("--- scanner 1 ---\n404,-588,-901").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Invalid("missing scanner 0"));
+    }
 }
@@ -77,89 +77,721 @@
 // position and depth you would have after following the planned course. What do
 // you get if you multiply your final horizontal position by your final depth?
 
-use std::str::FromStr;
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+
+use crate::parse::{self, ErrorKind, ParseError, ReadError};
+use crate::solution::Solution;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Action {
     Forward(i64),
     Down(i64),
     Up(i64),
+    Back(i64),
+    /// Turns by a number of quarter turns.
+    Left(i64),
+    Right(i64),
+    /// Goes straight up to the surface, levelling out.
+    Surface,
 }
 
-pub fn parse(txt: &str) -> Vec<Action> {
-    txt.lines()
-        .map(|l| {
-            let mut it = l.split_whitespace();
-            let dir = it.next().unwrap();
-            let dist = i64::from_str(it.next().unwrap()).unwrap();
-            match dir {
-                "forward" => Action::Forward(dist),
-                "down" => Action::Down(dist),
-                "up" => Action::Up(dist),
-                _ => unimplemented!(),
-            }
-        })
-        .collect()
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Forward(d) => write!(f, "forward {}", d),
+            Action::Down(d) => write!(f, "down {}", d),
+            Action::Up(d) => write!(f, "up {}", d),
+            Action::Back(d) => write!(f, "back {}", d),
+            Action::Left(d) => write!(f, "left {}", d),
+            Action::Right(d) => write!(f, "right {}", d),
+            Action::Surface => write!(f, "surface"),
+        }
+    }
 }
 
-pub fn part1(actions: impl IntoIterator<Item = Action>) -> i64 {
-    let mut horizontal = 0;
-    let mut depth = 0;
+fn parse_action(l: &parse::Line<'_>) -> Result<Action, ParseError> {
+    let mut it = l.text.split_whitespace();
+    let dir = l.field(&mut it)?;
+    let action: fn(i64) -> Action = match dir {
+        "forward" => Action::Forward,
+        "down" => Action::Down,
+        "up" => Action::Up,
+        "back" => Action::Back,
+        "left" => Action::Left,
+        "right" => Action::Right,
+        "surface" => return Ok(Action::Surface),
+        _ => return Err(l.error(dir, ErrorKind::UnknownKeyword)),
+    };
+    Ok(action(l.number(l.field(&mut it)?)?))
+}
 
-    for action in actions {
-        match action {
-            Action::Forward(d) => horizontal += d,
-            Action::Down(d) => depth += d,
-            Action::Up(d) => depth -= d,
+pub fn parse(txt: &str) -> Result<Vec<Action>, ParseError> {
+    parse::lines(txt).map(|l| parse_action(&l)).collect()
+}
+
+/// Which way the submarine faces. It starts out facing along `horizontal`,
+/// which is all the original commands ever need.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Heading {
+    #[default]
+    East,
+    North,
+    West,
+    South,
+}
+
+impl Heading {
+    /// Turns left by `quarters` quarter turns (right when negative).
+    pub fn turn(self, quarters: i64) -> Heading {
+        const ORDER: [Heading; 4] = [Heading::East, Heading::North, Heading::West, Heading::South];
+        ORDER[(self as i64 + quarters).rem_euclid(4) as usize]
+    }
+}
+
+/// Where the submarine is. `lateral` is the distance to the left of where it
+/// started, and `aim` is only used by models that steer with it.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SubmarineState {
+    pub horizontal: i64,
+    pub lateral: i64,
+    pub depth: i64,
+    pub aim: i64,
+    pub heading: Heading,
+}
+
+impl SubmarineState {
+    pub fn step(&mut self, model: &mut (impl NavigationModel + ?Sized), action: Action) {
+        model.step(self, action);
+    }
+
+    /// Moves `d` along the current heading, without changing depth.
+    pub fn advance(&mut self, d: i64) {
+        match self.heading {
+            Heading::East => self.horizontal += d,
+            Heading::North => self.lateral += d,
+            Heading::West => self.horizontal -= d,
+            Heading::South => self.lateral -= d,
         }
     }
 
-    depth * horizontal
+    pub fn product(&self) -> i64 {
+        self.depth * self.horizontal
+    }
 }
 
-pub fn part2(actions: impl IntoIterator<Item = Action>) -> i64 {
-    let mut horizontal = 0;
-    let mut depth = 0;
-    let mut aim = 0;
-    for action in actions {
+/// An interpretation of the commands. Models take `&mut self` so that they
+/// can keep track of what happened along the way.
+pub trait NavigationModel {
+    fn step(&mut self, state: &mut SubmarineState, action: Action);
+}
+
+/// Part 1: `down` and `up` change the depth directly.
+pub struct Direct;
+
+impl NavigationModel for Direct {
+    fn step(&mut self, state: &mut SubmarineState, action: Action) {
+        match action {
+            Action::Forward(d) => state.advance(d),
+            Action::Back(d) => state.advance(-d),
+            Action::Down(d) => state.depth += d,
+            Action::Up(d) => state.depth -= d,
+            Action::Left(q) => state.heading = state.heading.turn(q),
+            Action::Right(q) => state.heading = state.heading.turn(-q),
+            Action::Surface => state.depth = 0,
+        }
+    }
+}
+
+/// Part 2: `down` and `up` change the aim, and moving forward (or back)
+/// dives along it.
+pub struct Aim;
+
+impl NavigationModel for Aim {
+    fn step(&mut self, state: &mut SubmarineState, action: Action) {
         match action {
             Action::Forward(d) => {
-                horizontal += d;
-                depth += aim * d;
+                state.advance(d);
+                state.depth += state.aim * d;
+            }
+            Action::Back(d) => {
+                state.advance(-d);
+                state.depth -= state.aim * d;
             }
-            Action::Down(d) => aim += d,
-            Action::Up(d) => aim -= d,
+            Action::Down(d) => state.aim += d,
+            Action::Up(d) => state.aim -= d,
+            Action::Left(q) => state.heading = state.heading.turn(q),
+            Action::Right(q) => state.heading = state.heading.turn(-q),
+            Action::Surface => {
+                state.depth = 0;
+                state.aim = 0;
+            }
+        }
+    }
+}
+
+/// Raised when a step would have taken the submarine above the surface.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Clamp {
+    /// The index of the offending action.
+    pub step: usize,
+    /// How far above the surface it would have ended up.
+    pub overshoot: i64,
+}
+
+/// Wraps another model, keeping the depth from going negative and recording
+/// every time that happens.
+pub struct Surfaced<M> {
+    pub inner: M,
+    pub clamps: Vec<Clamp>,
+    steps: usize,
+}
+
+impl<M: NavigationModel> Surfaced<M> {
+    pub fn new(inner: M) -> Self {
+        Surfaced {
+            inner,
+            clamps: vec![],
+            steps: 0,
+        }
+    }
+}
+
+impl<M: NavigationModel> NavigationModel for Surfaced<M> {
+    fn step(&mut self, state: &mut SubmarineState, action: Action) {
+        self.inner.step(state, action);
+        if state.depth < 0 {
+            self.clamps.push(Clamp {
+                step: self.steps,
+                overshoot: -state.depth,
+            });
+            state.depth = 0;
+        }
+        self.steps += 1;
+    }
+}
+
+/// Wraps another model, recording where the submarine is after every step.
+/// Build it with `Recorded::new` to have the starting position recorded too.
+pub struct Recorded<M> {
+    pub inner: M,
+    pub trajectory: Vec<SubmarineState>,
+}
+
+impl<M: NavigationModel> Recorded<M> {
+    pub fn new(inner: M) -> Self {
+        Recorded {
+            inner,
+            trajectory: vec![SubmarineState::default()],
+        }
+    }
+}
+
+impl<M: NavigationModel> NavigationModel for Recorded<M> {
+    fn step(&mut self, state: &mut SubmarineState, action: Action) {
+        self.inner.step(state, action);
+        self.trajectory.push(*state);
+    }
+}
+
+/// Writes one line per position, after a header.
+pub fn trajectory_csv(trajectory: &[SubmarineState]) -> String {
+    let mut out = "step,horizontal,lateral,depth,aim,heading\n".to_owned();
+    for (step, s) in trajectory.iter().enumerate() {
+        out.push_str(&format!(
+            "{},{},{},{},{},{:?}\n",
+            step, s.horizontal, s.lateral, s.depth, s.aim, s.heading
+        ));
+    }
+    out
+}
+
+// Escapes text for use in SVG (or any XML) content and attribute values.
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
         }
     }
+    out
+}
 
-    depth * horizontal
+/// Draws each named course as a polyline of depth (downwards) against
+/// horizontal distance, all on the same axes so they can be compared.
+pub fn trajectory_svg(courses: &[(&str, &[SubmarineState])]) -> String {
+    const COLOURS: [&str; 4] = ["steelblue", "crimson", "seagreen", "darkorange"];
+
+    let points = courses.iter().flat_map(|(_, t)| t.iter());
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (0, 0, 0, 0);
+    for s in points {
+        min_x = min_x.min(s.horizontal);
+        max_x = max_x.max(s.horizontal);
+        min_y = min_y.min(s.depth);
+        max_y = max_y.max(s.depth);
+    }
+    let margin = ((max_x - min_x).max(max_y - min_y) / 20).max(1);
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+        min_x - margin,
+        min_y - margin,
+        max_x - min_x + 2 * margin,
+        max_y - min_y + 2 * margin
+    );
+    for (i, (name, trajectory)) in courses.iter().enumerate() {
+        let points = trajectory
+            .iter()
+            .map(|s| format!("{},{}", s.horizontal, s.depth))
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push_str(&format!(
+            "  <polyline fill=\"none\" stroke=\"{}\" vector-effect=\"non-scaling-stroke\" points=\"{}\"><title>{}</title></polyline>\n",
+            COLOURS[i % COLOURS.len()],
+            points,
+            escape_xml(name)
+        ));
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+pub fn navigate(
+    model: &mut (impl NavigationModel + ?Sized),
+    actions: impl IntoIterator<Item = Action>,
+) -> SubmarineState {
+    let mut state = SubmarineState::default();
+    for action in actions {
+        state.step(model, action);
+    }
+    state
+}
+
+pub fn part1(actions: impl IntoIterator<Item = Action>) -> i64 {
+    navigate(&mut Direct, actions).product()
+}
+
+pub fn part2(actions: impl IntoIterator<Item = Action>) -> i64 {
+    navigate(&mut Aim, actions).product()
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PlanError {
+    /// No sequence of commands gets there, however long: the target is
+    /// behind the start, or directly below it.
+    Unreachable,
+    /// Every plan would need more than `Planner::max_actions` commands.
+    TooLong,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::Unreachable => write!(f, "target is unreachable"),
+            PlanError::TooLong => write!(f, "no plan within the action limit"),
+        }
+    }
+}
+
+impl Error for PlanError {}
+
+/// Works out the fewest `forward`, `down` and `up` commands which take the
+/// `Aim` model from the start to a given horizontal position and depth. All
+/// command arguments are positive.
+#[derive(Copy, Clone, Debug)]
+pub struct Planner {
+    /// The largest argument any one command may have.
+    pub max_step: Option<i64>,
+    pub max_actions: usize,
+}
+
+fn aim_change(delta: i64) -> Action {
+    if delta > 0 {
+        Action::Down(delta)
+    } else {
+        Action::Up(-delta)
+    }
+}
+
+impl Planner {
+    pub fn plan(&self, horizontal: i64, depth: i64) -> Result<Vec<Action>, PlanError> {
+        if (horizontal, depth) == (0, 0) {
+            return Ok(vec![]);
+        }
+        if horizontal <= 0 || self.max_step.is_some_and(|m| m < 1) {
+            return Err(PlanError::Unreachable);
+        }
+        let plan = match self.max_step {
+            None => Some(Self::unlimited(horizontal, depth)),
+            Some(max_step) => (0..=self.max_actions).find_map(|n| {
+                let mut plan = Vec::with_capacity(n);
+                let target = (horizontal, depth, max_step);
+                Self::search(target, (0, 0, 0), None, n, &mut plan).then_some(plan)
+            }),
+        };
+        plan.filter(|p| p.len() <= self.max_actions)
+            .ok_or(PlanError::TooLong)
+    }
+
+    // Without a step limit, any target with a positive horizontal position
+    // takes at most three commands: go all but one step, aim at the whole
+    // depth, and take the last step.
+    fn unlimited(horizontal: i64, depth: i64) -> Vec<Action> {
+        if depth == 0 {
+            return vec![Action::Forward(horizontal)];
+        }
+        if depth % horizontal == 0 {
+            return vec![aim_change(depth / horizontal), Action::Forward(horizontal)];
+        }
+        // horizontal > 1 here, or it would have divided depth
+        vec![
+            Action::Forward(horizontal - 1),
+            aim_change(depth),
+            Action::Forward(1),
+        ]
+    }
+
+    // Depth-first search for a plan of exactly `n` more commands. Splitting a
+    // move into several commands only ever happens in whole `max_step`
+    // pieces, which cuts out most of the equivalent orderings.
+    fn search(
+        target: (i64, i64, i64),
+        (h, d, aim): (i64, i64, i64),
+        last: Option<Action>,
+        n: usize,
+        plan: &mut Vec<Action>,
+    ) -> bool {
+        let (horizontal, depth, max_step) = target;
+        let remaining = horizontal - h;
+        if remaining == 0 {
+            return n == 0 && d == depth;
+        }
+        let forwards = (remaining as u64).div_ceil(max_step as u64) as usize;
+        let on_course = d + aim * remaining == depth;
+        if n < forwards + usize::from(!on_course) {
+            return false;
+        }
+        // how far the aim could possibly move with the commands left over
+        let swing = max_step as i128 * (n - forwards) as i128;
+        let (r, d, a) = (remaining as i128, d as i128, aim as i128);
+        if !(d + r * (a - swing)..=d + r * (a + swing)).contains(&(depth as i128)) {
+            return false;
+        }
+        let d = d as i64;
+
+        let attempt = |action: Action, next: (i64, i64, i64), plan: &mut Vec<Action>| {
+            plan.push(action);
+            if Self::search(target, next, Some(action), n - 1, plan) {
+                return true;
+            }
+            plan.pop();
+            false
+        };
+
+        let forward_ok = match last {
+            Some(Action::Forward(f)) => f == max_step,
+            _ => true,
+        };
+        if forward_ok {
+            for f in (1..=max_step.min(remaining)).rev() {
+                if attempt(Action::Forward(f), (h + f, d + aim * f, aim), plan) {
+                    return true;
+                }
+            }
+        }
+        for v in 1..=max_step {
+            let down_ok = match last {
+                Some(Action::Down(p)) => p == max_step,
+                Some(Action::Up(_)) => false,
+                _ => true,
+            };
+            if down_ok && attempt(Action::Down(v), (h, d, aim + v), plan) {
+                return true;
+            }
+            let up_ok = match last {
+                Some(Action::Up(p)) => p == max_step,
+                Some(Action::Down(_)) => false,
+                _ => true,
+            };
+            if up_ok && attempt(Action::Up(v), (h, d, aim - v), plan) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+pub fn read_part1(r: impl BufRead) -> Result<i64, ReadError> {
+    parse::process_records(parse::records(r, parse_action), |actions| part1(actions))
+}
+
+pub fn read_part2(r: impl BufRead) -> Result<i64, ReadError> {
+    parse::process_records(parse::records(r, parse_action), |actions| part2(actions))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: usize = 2;
+
+    type Input = Vec<Action>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        part1(input.iter().copied())
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(part2(input.iter().copied()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse, part1, part2};
+    use std::collections::{HashMap, HashSet};
+
+    use super::{
+        navigate, parse, part1, part2, read_part1, read_part2, trajectory_csv, trajectory_svg,
+        Action, Aim, Clamp, Direct, Heading, NavigationModel, PlanError, Planner, Recorded,
+        SubmarineState, Surfaced,
+    };
+    use crate::parse::{ErrorKind, ReadError};
 
     #[test]
     fn test_day_2_example_part1() {
         let example_input = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
-        assert_eq!(part1(parse(example_input)), 150);
+        assert_eq!(part1(parse(example_input).unwrap()), 150);
     }
 
     #[test]
     fn test_day_2_example_part2() {
         let example_input = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
-        assert_eq!(part2(parse(example_input)), 900);
+        assert_eq!(part2(parse(example_input).unwrap()), 900);
     }
 
     #[test]
     fn test_day_2_part1() {
         let input = include_str!("input/day_2.txt");
-        assert_eq!(part1(parse(input)), 2215080);
+        assert_eq!(part1(parse(input).unwrap()), 2215080);
     }
 
     #[test]
     fn test_day_2_part2() {
         let input = include_str!("input/day_2.txt");
-        assert_eq!(part2(parse(input)), 1864715580);
+        assert_eq!(part2(parse(input).unwrap()), 1864715580);
+    }
+
+    #[test]
+    fn test_day_2_parse_error() {
+        let e = parse("forward 5\nsideways 3\n").unwrap_err();
+        assert_eq!(
+            (e.line, e.column, e.kind),
+            (2, 1, ErrorKind::UnknownKeyword)
+        );
+        let e = parse("forward 5\ndown\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (2, 5, ErrorKind::MissingField));
+    }
+
+    #[test]
+    fn test_day_2_read() {
+        let input = include_str!("input/day_2.txt");
+        assert_eq!(read_part1(input.as_bytes()).unwrap(), 2215080);
+        assert_eq!(read_part2(input.as_bytes()).unwrap(), 1864715580);
+        match read_part2("forward 5\nsideways 3\n".as_bytes()) {
+            Err(ReadError::Parse(e)) => assert_eq!(e.kind, ErrorKind::UnknownKeyword),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_day_2_models() {
+        let mut state = SubmarineState::default();
+        state.step(&mut Aim, Action::Down(5));
+        state.step(&mut Aim, Action::Forward(8));
+        assert_eq!(
+            state,
+            SubmarineState {
+                horizontal: 8,
+                depth: 40,
+                aim: 5,
+                ..Default::default()
+            }
+        );
+
+        let actions = parse("down 2\nup 5\nforward 1\ndown 4\nup 1").unwrap();
+        let models: [&mut dyn NavigationModel; 2] = [&mut Direct, &mut Aim];
+        let depths = models
+            .map(|m| navigate(m, actions.iter().copied()).depth)
+            .to_vec();
+        assert_eq!(depths, vec![0, -3]);
+
+        let mut surfaced = Surfaced::new(Direct);
+        let state = navigate(&mut surfaced, actions.iter().copied());
+        assert_eq!(state.depth, 3);
+        assert_eq!(
+            surfaced.clamps,
+            vec![Clamp {
+                step: 1,
+                overshoot: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn test_day_2_extended_commands() {
+        let input = "forward 2\nleft 1\nforward 3\nright 2\nback 1\ndown 4\nforward 1\nsurface";
+        let actions = parse(input).unwrap();
+        assert_eq!(actions[7], Action::Surface);
+        assert_eq!(
+            actions.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+            input.lines().collect::<Vec<_>>()
+        );
+
+        let state = navigate(&mut Direct, actions[..7].iter().copied());
+        assert_eq!((state.horizontal, state.lateral, state.depth), (2, 3, 4));
+        assert_eq!(state.heading, Heading::South);
+        let state = navigate(&mut Aim, actions.iter().copied());
+        assert_eq!((state.lateral, state.depth, state.aim), (3, 0, 0));
+        assert_eq!(Heading::East.turn(-5), Heading::South);
+
+        let e = parse("left x").unwrap_err();
+        assert_eq!((e.column, e.kind), (6, ErrorKind::InvalidNumber));
+        let e = parse("surface\nstop").unwrap_err();
+        assert_eq!((e.line, e.kind), (2, ErrorKind::UnknownKeyword));
+    }
+
+    #[test]
+    fn test_day_2_trajectory() {
+        let actions = parse("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n").unwrap();
+        let mut direct = Recorded::new(Direct);
+        let end = navigate(&mut direct, actions.iter().copied());
+        let mut aim = Recorded::new(Aim);
+        navigate(&mut aim, actions.iter().copied());
+        assert_eq!(direct.trajectory.len(), 7);
+        assert_eq!(direct.trajectory.last(), Some(&end));
+        assert_eq!(aim.trajectory[3].depth, 40);
+
+        let csv = trajectory_csv(&direct.trajectory);
+        assert_eq!(
+            csv.lines().take(3).collect::<Vec<_>>(),
+            vec![
+                "step,horizontal,lateral,depth,aim,heading",
+                "0,0,0,0,0,East",
+                "1,5,0,0,0,East"
+            ]
+        );
+
+        let svg = trajectory_svg(&[("direct", &direct.trajectory), ("aim", &aim.trajectory)]);
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-3 -3 21 66\">")
+        );
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains("points=\"0,0 5,0 5,5 13,5 13,2 13,10 15,10\""));
+        assert!(svg.contains("<title>direct</title>"));
+
+        let svg = trajectory_svg(&[("<aim> & \"drift\"", &aim.trajectory)]);
+        assert!(svg.contains("<title>&lt;aim&gt; &amp; &quot;drift&quot;</title>"));
+    }
+
+    #[test]
+    fn test_day_2_planner() {
+        let unlimited = Planner {
+            max_step: None,
+            max_actions: 10,
+        };
+        let limited = Planner {
+            max_step: Some(6),
+            max_actions: 10,
+        };
+        for horizontal in 0..=5 {
+            for depth in -6..=6 {
+                let plan = match unlimited.plan(horizontal, depth) {
+                    Ok(plan) => plan,
+                    Err(e) => {
+                        assert_eq!((horizontal, e), (0, PlanError::Unreachable));
+                        continue;
+                    }
+                };
+                let end = navigate(&mut Aim, plan.iter().copied());
+                assert_eq!((end.horizontal, end.depth), (horizontal, depth));
+                // a step limit this generous never makes the plan longer
+                assert_eq!(limited.plan(horizontal, depth).unwrap().len(), plan.len());
+            }
+        }
+        assert_eq!(
+            unlimited.plan(15, 60).unwrap(),
+            vec![Action::Down(4), Action::Forward(15)]
+        );
+        // a prime depth has no divisor to share the aim with
+        let plan = unlimited.plan(2_000_000_000, 2_147_483_647).unwrap();
+        assert_eq!(
+            plan,
+            vec![
+                Action::Forward(1_999_999_999),
+                Action::Down(2_147_483_647),
+                Action::Forward(1)
+            ]
+        );
+        let end = navigate(&mut Aim, plan);
+        assert_eq!((end.horizontal, end.depth), (2_000_000_000, 2_147_483_647));
+
+        let tight = Planner {
+            max_step: Some(3),
+            max_actions: 6,
+        };
+        let plan = tight.plan(7, 20).unwrap();
+        assert_eq!(plan.len(), 5);
+        assert!(plan.iter().all(|a| matches!(
+            a,
+            Action::Forward(1..=3) | Action::Down(1..=3) | Action::Up(1..=3)
+        )));
+        let end = navigate(&mut Aim, plan.iter().copied());
+        assert_eq!((end.horizontal, end.depth), (7, 20));
+
+        // the fewest commands to reach each position, breadth first over
+        // every state the tight planner could pass through
+        let mut fewest = HashMap::from([((0, 0), 0)]);
+        let mut frontier = vec![SubmarineState::default()];
+        let mut seen = HashSet::new();
+        for len in 1..=tight.max_actions {
+            let mut next = vec![];
+            for state in frontier {
+                for k in 1..=3 {
+                    for action in [Action::Forward(k), Action::Down(k), Action::Up(k)] {
+                        let mut state = state;
+                        state.step(&mut Aim, action);
+                        if seen.insert((state.horizontal, state.depth, state.aim)) {
+                            fewest.entry((state.horizontal, state.depth)).or_insert(len);
+                            next.push(state);
+                        }
+                    }
+                }
+            }
+            frontier = next;
+        }
+        for horizontal in 0..=8 {
+            for depth in -12..=24 {
+                let expected = match fewest.get(&(horizontal, depth)) {
+                    Some(len) => Ok(*len),
+                    None if horizontal == 0 => Err(PlanError::Unreachable),
+                    None => Err(PlanError::TooLong),
+                };
+                let plan = tight.plan(horizontal, depth);
+                assert_eq!(plan.map(|p| p.len()), expected, "{},{}", horizontal, depth);
+            }
+        }
+        assert_eq!(tight.plan(7, 200), Err(PlanError::TooLong));
+        assert_eq!(tight.plan(-1, 0), Err(PlanError::Unreachable));
     }
 }
@@ -153,22 +153,31 @@
 
 use std::collections::HashSet;
 
-pub fn print_img(img: &HashSet<(isize, isize)>) {
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+use crate::trace;
+use crate::trace::{Silent, Tracer};
+
+pub fn render_img(img: &HashSet<(isize, isize)>) -> String {
+    let mut out = String::new();
     let min_x = img.iter().map(|(x, _)| *x).min().unwrap();
     let max_x = img.iter().map(|(x, _)| *x).max().unwrap();
-    let min_y = img.iter().map(|(y, _)| *y).min().unwrap();
-    let max_y = img.iter().map(|(y, _)| *y).max().unwrap();
+    let min_y = img.iter().map(|(_, y)| *y).min().unwrap();
+    let max_y = img.iter().map(|(_, y)| *y).max().unwrap();
 
     for x in min_x..=max_x {
         for y in min_y..=max_y {
             if img.contains(&(x, y)) {
-                eprint!("#");
+                out.push('#');
             } else {
-                eprint!(".");
+                out.push('.');
             }
         }
-        eprintln!();
+        if x != max_x {
+            out.push('\n');
+        }
     }
+    out
 }
 
 pub fn get_pix(
@@ -221,29 +230,41 @@ fn enhance_img(
     )
 }
 
-pub fn part_1(s: &str) -> usize {
-    let mut iter = s.lines();
-    let algo = iter
-        .next()
-        .unwrap()
+#[derive(Debug)]
+pub struct Image {
+    pub algo: Vec<bool>,
+    pub lit: HashSet<(isize, isize)>,
+    pub bounds: (isize, isize, isize, isize),
+}
+
+fn pixel(line: &parse::Line<'_>, c: char, at: &str) -> Result<bool, ParseError> {
+    match c {
+        '.' => Ok(false),
+        '#' => Ok(true),
+        _ => Err(line.error(at, ErrorKind::UnexpectedChar)),
+    }
+}
+
+pub fn parse(s: &str) -> Result<Image, ParseError> {
+    let mut iter = parse::lines(s);
+    let line = iter.expect()?;
+    let algo = line
         .chars()
-        .map(|c| match c {
-            '.' => false,
-            '#' => true,
-            _ => unreachable!(),
-        })
-        .collect::<Vec<bool>>();
-    assert_eq!(algo.len(), 512);
+        .map(|(c, at)| pixel(&line, c, at))
+        .collect::<Result<Vec<bool>, _>>()?;
+    if algo.len() != 512 {
+        return Err(line.error(line.text, ErrorKind::Invalid("expected 512 pixels")));
+    }
 
-    let _ = iter.next().unwrap();
+    iter.blank()?;
 
     let mut lit: HashSet<(isize, isize)> = HashSet::new();
 
     let mut max_row = 0;
     let mut max_col = 0;
     for (row, line) in iter.enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            if c == '#' {
+        for (col, (c, at)) in line.chars().enumerate() {
+            if pixel(&line, c, at)? {
                 lit.insert((row as isize, col as isize));
             }
             max_col = max_col.max(col);
@@ -251,78 +272,91 @@ pub fn part_1(s: &str) -> usize {
         max_row = row;
     }
 
-    let (e1, e1b, e1bv) = enhance_img(
-        &lit,
-        &algo,
-        (0, max_row as isize, 0, max_col as isize),
-        false,
-    );
-    let (e2, _, _) = enhance_img(&e1, &algo, e1bv, e1b);
+    Ok(Image {
+        algo,
+        lit,
+        bounds: (0, max_row as isize, 0, max_col as isize),
+    })
+}
 
-    e2.len()
+fn enhance(image: &Image, steps: usize) -> HashSet<(isize, isize)> {
+    let mut e = (image.lit.clone(), false, image.bounds);
+    for _ in 0..steps {
+        e = enhance_img(&e.0, &image.algo, e.2, e.1);
+    }
+    e.0
 }
 
-pub fn part_2(s: &str) -> usize {
-    let mut iter = s.lines();
-    let algo = iter
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| match c {
-            '.' => false,
-            '#' => true,
-            _ => unreachable!(),
-        })
-        .collect::<Vec<bool>>();
-    assert_eq!(algo.len(), 512);
+fn lit_after_two(image: &Image) -> usize {
+    enhance(image, 2).len()
+}
 
-    let _ = iter.next().unwrap();
+fn lit_after_fifty(image: &Image, tracer: &mut dyn Tracer) -> usize {
+    let img = enhance(image, 50);
+    if tracer.enabled() {
+        trace!(tracer, "{}", render_img(&img));
+    }
 
-    let mut lit: HashSet<(isize, isize)> = HashSet::new();
+    img.len()
+}
 
-    let mut max_row = 0;
-    let mut max_col = 0;
-    for (row, line) in iter.enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            if c == '#' {
-                lit.insert((row as isize, col as isize));
-            }
-            max_col = max_col.max(col);
-        }
-        max_row = row;
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(lit_after_two(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(lit_after_fifty(&parse(s)?, &mut Silent))
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: usize = 20;
+
+    type Input = Image;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        lit_after_two(input)
     }
 
-    let mut e = (lit, false, (0, max_row as isize, 0, max_col as isize));
-    for _ in 0..50 {
-        e = enhance_img(&e.0, &algo, e.2, e.1);
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(lit_after_fifty(input, &mut Silent))
     }
-    print_img(&e.0);
 
-    e.0.len()
+    fn part_2_traced(input: &Self::Input, tracer: &mut dyn Tracer) -> Option<Self::Answer2> {
+        Some(lit_after_fifty(input, tracer))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     #[test]
     fn test_day_20_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 35);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 35);
     }
 
     #[test]
     fn test_day_20_part_1() {
-        assert_eq!(part_1(include_str!("input/day_20.txt")), 5291);
+        assert_eq!(part_1(include_str!("input/day_20.txt")).unwrap(), 5291);
     }
 
     #[test]
     fn test_day_20_example_part_2() {
-        assert_eq!(part_2(EXAMPLE), 3351);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 3351);
     }
 
     #[test]
     fn test_day_20_part_2() {
-        let answer = part_2(include_str!("input/day_20.txt"));
+        let answer = part_2(include_str!("input/day_20.txt")).unwrap();
         assert_eq!(answer, 16665);
     }
 
@@ -333,4 +367,13 @@ mod tests {
 ##..#
 ..#..
 ..###"#;
+
+    #[test]
+    fn test_day_20_parse_error() {
+        let algo = EXAMPLE.lines().next().unwrap();
+        let e = parse(&format!("{}\n\n#..#.\n#.o..", algo)).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (4, 3, "o"));
+        let e = parse("..#.#\n\n#..#.").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Invalid("expected 512 pixels"));
+    }
 }
@@ -83,6 +83,9 @@
 // the player that wins in more universes; in how many universes does that
 // player win?
 
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct DeterministicDice(u8, usize);
 
@@ -105,26 +108,24 @@ impl DeterministicDice {
     }
 }
 
-pub fn part_1(s: &str) -> usize {
-    let mut iter = s.lines();
-    let mut p1_position: usize = iter
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .rev()
-        .next()
-        .unwrap()
-        .parse()
-        .unwrap();
-    let mut p2_position: usize = iter
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .rev()
-        .next()
-        .unwrap()
-        .parse()
-        .unwrap();
+pub fn parse(s: &str) -> Result<(usize, usize), ParseError> {
+    let mut lines = parse::lines(s);
+    let mut position = |player: &str| {
+        let line = lines.expect()?;
+        let (name, pos) = line.split_once(line.text, " starting position: ")?;
+        if name != player {
+            return Err(line.error(name, ErrorKind::UnknownKeyword));
+        }
+        match line.number(pos)? {
+            pos @ 1..=10 => Ok(pos),
+            _ => Err(line.error(pos, ErrorKind::Invalid("position must be 1 to 10"))),
+        }
+    };
+    Ok((position("Player 1")?, position("Player 2")?))
+}
+
+fn deterministic_game(players: &(usize, usize)) -> usize {
+    let (mut p1_position, mut p2_position) = *players;
     let mut p1_score = 0;
     let mut p2_score = 0;
 
@@ -168,26 +169,8 @@ pub fn part_1(s: &str) -> usize {
     p1_score.min(p2_score) * dice.count()
 }
 
-pub fn part_2(s: &str) -> usize {
-    let mut iter = s.lines();
-    let p1_position: usize = iter
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .rev()
-        .next()
-        .unwrap()
-        .parse()
-        .unwrap();
-    let p2_position: usize = iter
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .rev()
-        .next()
-        .unwrap()
-        .parse()
-        .unwrap();
+fn dirac_wins(players: &(usize, usize)) -> usize {
+    let (p1_position, p2_position) = *players;
 
     #[allow(clippy::enum_variant_names)]
     #[derive(Copy, Clone)]
@@ -296,31 +279,70 @@ pub fn part_2(s: &str) -> usize {
     p1_wins.max(p2_wins)
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(deterministic_game(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(dirac_wins(&parse(s)?))
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: usize = 21;
+
+    type Input = (usize, usize);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        deterministic_game(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(dirac_wins(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     #[test]
     fn test_day_21_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 739785);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 739785);
     }
 
     #[test]
     fn test_day_21_part_1() {
-        assert_eq!(part_1(include_str!("input/day_21.txt")), 713328);
+        assert_eq!(part_1(include_str!("input/day_21.txt")).unwrap(), 713328);
     }
 
     #[test]
     fn test_day_21_example_part_2() {
-        assert_eq!(part_2(EXAMPLE), 444356092776315);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 444356092776315);
     }
 
     #[test]
     fn test_day_21_part_2() {
-        let answer = part_2(include_str!("input/day_21.txt"));
+        let answer = part_2(include_str!("input/day_21.txt")).unwrap();
         assert_eq!(answer, 92399285032143);
     }
 
     const EXAMPLE: &str = r#"Player 1 starting position: 4
 Player 2 starting position: 8"#;
+
+    #[test]
+    fn test_day_21_parse_error() {
+        let e = parse("Player 1 starting position: 4\nPlayer 2 starting position: 11").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 29, "11"));
+        let e = parse("Player 1 starting position: 4").unwrap_err();
+        assert_eq!((e.line, e.kind), (2, ErrorKind::UnexpectedEnd));
+    }
 }
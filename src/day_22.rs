@@ -204,15 +204,19 @@
 // Starting again with all cubes off, execute all reboot steps. Afterward,
 // considering all cubes, how many cubes are on?
 use std::collections::HashMap;
+use std::io::BufRead;
+
+use crate::parse::{self, ErrorKind, ParseError, ReadError};
+use crate::solution::Solution;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-struct Ranges {
-    x_min: isize,
-    x_max: isize,
-    y_min: isize,
-    y_max: isize,
-    z_min: isize,
-    z_max: isize,
+pub struct Ranges {
+    pub x_min: isize,
+    pub x_max: isize,
+    pub y_min: isize,
+    pub y_max: isize,
+    pub z_min: isize,
+    pub z_max: isize,
 }
 
 impl Ranges {
@@ -342,47 +346,59 @@ impl IndexedRanges {
     }
 }
 
-fn common(s: &str) -> Vec<Ranges> {
-    let mut on_ranges = IndexedRanges::new();
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Step {
+    pub on: bool,
+    pub ranges: Ranges,
+}
 
-    for line in s.lines() {
-        let (onoff, ranges) = line.split_once(' ').unwrap();
-        let on = match onoff {
-            "on" => true,
-            "off" => false,
-            _ => unreachable!(),
-        };
-
-        let mut r = Ranges {
-            x_min: 0,
-            x_max: 0,
-            y_min: 0,
-            y_max: 0,
-            z_min: 0,
-            z_max: 0,
-        };
-
-        for coord_range in ranges.split(',') {
-            let (axis, range_str) = coord_range.split_once('=').unwrap();
-            let (min, max) = range_str.split_once("..").unwrap();
-
-            match axis {
-                "x" => {
-                    r.x_min = min.parse().unwrap();
-                    r.x_max = max.parse().unwrap();
-                }
-                "y" => {
-                    r.y_min = min.parse().unwrap();
-                    r.y_max = max.parse().unwrap();
-                }
-                "z" => {
-                    r.z_min = min.parse().unwrap();
-                    r.z_max = max.parse().unwrap();
-                }
-                _ => unreachable!(),
-            }
+fn parse_step(line: &parse::Line<'_>) -> Result<Step, ParseError> {
+    let (onoff, ranges) = line.split_once(line.text, " ")?;
+    let on = match onoff {
+        "on" => true,
+        "off" => false,
+        _ => return Err(line.error(onoff, ErrorKind::UnknownKeyword)),
+    };
+
+    let mut coords = ranges.split(',');
+    let mut range = |want: &str| {
+        let coord_range = line.field(&mut coords)?;
+        let (axis, range_str) = line.split_once(coord_range, "=")?;
+        if axis != want {
+            return Err(line.error(axis, ErrorKind::UnknownKeyword));
         }
+        let (min, max) = line.split_once(range_str, "..")?;
+        Ok((line.number(min)?, line.number(max)?))
+    };
+    let (x_min, x_max) = range("x")?;
+    let (y_min, y_max) = range("y")?;
+    let (z_min, z_max) = range("z")?;
+    if let Some(extra) = coords.next() {
+        return Err(line.error(extra, ErrorKind::UnexpectedChar));
+    }
+
+    Ok(Step {
+        on,
+        ranges: Ranges {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+            z_min,
+            z_max,
+        },
+    })
+}
 
+pub fn parse(s: &str) -> Result<Vec<Step>, ParseError> {
+    parse::lines(s).map(|line| parse_step(&line)).collect()
+}
+
+// Takes the steps by value so that they can be applied as they're read.
+fn common(steps: impl IntoIterator<Item = Step>) -> Vec<Ranges> {
+    let mut on_ranges = IndexedRanges::new();
+
+    for Step { on, ranges: r } in steps {
         let to_subtract = on_ranges.find_overlaps(r);
 
         if on {
@@ -410,9 +426,7 @@ fn common(s: &str) -> Vec<Ranges> {
     on_ranges.contents().collect()
 }
 
-pub fn part_1(s: &str) -> isize {
-    let mut on_ranges = common(s);
-
+fn initialization_volume(on_ranges: Vec<Ranges>) -> isize {
     let limit = Ranges {
         x_min: -50,
         x_max: 50,
@@ -422,45 +436,97 @@ pub fn part_1(s: &str) -> isize {
         z_max: 50,
     };
 
-    on_ranges = on_ranges
+    let on_ranges = on_ranges
         .into_iter()
         .flat_map(|r| r.overlap(limit))
         .collect();
 
-    on_ranges.iter().map(|r| r.volume()).sum::<isize>()
+    reboot_volume(on_ranges)
 }
 
-pub fn part_2(s: &str) -> isize {
-    let on_ranges = common(s);
+fn reboot_volume(on_ranges: Vec<Ranges>) -> isize {
     on_ranges.iter().map(|r| r.volume()).sum::<isize>()
 }
 
+pub fn part_1(s: &str) -> Result<isize, ParseError> {
+    Ok(initialization_volume(common(parse(s)?)))
+}
+
+pub fn part_2(s: &str) -> Result<isize, ParseError> {
+    Ok(reboot_volume(common(parse(s)?)))
+}
+
+pub fn read_part_1(r: impl BufRead) -> Result<isize, ReadError> {
+    let steps = parse::records(r, parse_step);
+    Ok(initialization_volume(parse::process_records(
+        steps,
+        |steps| common(steps),
+    )?))
+}
+
+pub fn read_part_2(r: impl BufRead) -> Result<isize, ReadError> {
+    let steps = parse::records(r, parse_step);
+    Ok(reboot_volume(parse::process_records(steps, |steps| {
+        common(steps)
+    })?))
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: usize = 22;
+
+    type Input = Vec<Step>;
+    type Answer1 = isize;
+    type Answer2 = isize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        initialization_volume(common(input.iter().copied()))
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(reboot_volume(common(input.iter().copied())))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2, read_part_1, read_part_2};
+    use crate::parse::ErrorKind;
 
     #[test]
     fn test_day_22_example_part_1() {
-        assert_eq!(part_1(EXAMPLE2), 39);
-        assert_eq!(part_1(EXAMPLE), 590784);
+        assert_eq!(part_1(EXAMPLE2).unwrap(), 39);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 590784);
     }
 
     #[test]
     fn test_day_22_part_1() {
-        assert_eq!(part_1(include_str!("input/day_22.txt")), 545118);
+        assert_eq!(part_1(include_str!("input/day_22.txt")).unwrap(), 545118);
     }
 
     #[test]
     fn test_day_22_example_part_2() {
-        assert_eq!(part_2(EXAMPLE3), 2758514936282235);
+        assert_eq!(part_2(EXAMPLE3).unwrap(), 2758514936282235);
     }
 
     #[test]
     fn test_day_22_part_2() {
-        let answer = part_2(include_str!("input/day_22.txt"));
+        let answer = part_2(include_str!("input/day_22.txt")).unwrap();
         assert_eq!(answer, 1227298136842375);
     }
 
+    #[test]
+    fn test_day_22_read() {
+        assert_eq!(read_part_1(EXAMPLE.as_bytes()).unwrap(), 590784);
+        assert_eq!(read_part_2(EXAMPLE3.as_bytes()).unwrap(), 2758514936282235);
+        assert!(read_part_2("on x=10..12,y=10..12".as_bytes()).is_err());
+    }
+
     const EXAMPLE2: &str = r#"on x=10..12,y=10..12,z=10..12
 on x=11..13,y=11..13,z=11..13
 off x=9..11,y=9..11,z=9..11
@@ -548,4 +614,17 @@ off x=-27365..46395,y=31009..98017,z=15428..76570
 off x=-70369..-16548,y=22648..78696,z=-1892..86821
 on x=-53470..21291,y=-120233..-33476,z=-44150..38147
 off x=-93533..-4276,y=-16170..68771,z=-104985..-24507"#;
+
+    #[test]
+    fn test_day_22_parse_error() {
+        let e = parse("on x=10..12,y=10..12,z=10..12\nof x=9..11,y=9..11,z=9..11").unwrap_err();
+        assert_eq!(
+            (e.line, e.column, e.kind),
+            (2, 1, ErrorKind::UnknownKeyword)
+        );
+        let e = parse("on x=10..12,y=10..12").unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (1, 21, ErrorKind::MissingField));
+        let e = parse("on x=10..12,y=10..1a2,z=10..12").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (19, "1a2"));
+    }
 }
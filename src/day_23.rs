@@ -104,6 +104,11 @@
 
 use std::collections::{BTreeMap, HashMap, HashSet};
 
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+use crate::trace;
+use crate::trace::{Silent, Tracer};
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 enum Amphipod {
     A,
@@ -119,10 +124,11 @@ enum NodeType {
     OutsideRoom,
 }
 
-fn print_map(
+fn render_map(
     nodes: &HashMap<(isize, isize), NodeType>,
     amphipods: &BTreeMap<(isize, isize), Amphipod>,
-) {
+) -> String {
+    let mut out = String::new();
     let min_i = nodes.keys().map(|(i, _)| *i).min().unwrap();
     let max_i = nodes.keys().map(|(i, _)| *i).max().unwrap();
     let min_j = nodes.keys().map(|(_, j)| *j).min().unwrap();
@@ -131,19 +137,20 @@ fn print_map(
     for i in min_i..=max_i {
         for j in min_j..=max_j {
             match (nodes.get(&(i, j)), amphipods.get(&(i, j))) {
-                (Some(NodeType::Hallway), None) | (Some(NodeType::Room), None) => {
-                    eprint!(".")
-                }
-                (Some(NodeType::OutsideRoom), None) => eprint!("x"),
+                (Some(NodeType::Hallway), None) | (Some(NodeType::Room), None) => out.push('.'),
+                (Some(NodeType::OutsideRoom), None) => out.push('x'),
                 (Some(NodeType::Hallway), Some(a))
                 | (Some(NodeType::Room), Some(a))
-                | (Some(NodeType::OutsideRoom), Some(a)) => eprint!("{:?}", a),
-                (None, None) => eprint!(" "),
+                | (Some(NodeType::OutsideRoom), Some(a)) => out.push_str(&format!("{:?}", a)),
+                (None, None) => out.push(' '),
                 _ => unreachable!(),
             }
         }
-        eprintln!();
+        if i != max_i {
+            out.push('\n');
+        }
     }
+    out
 }
 
 const fn cost(a: Amphipod) -> isize {
@@ -168,6 +175,7 @@ fn in_correct_room(amphipod_loc: (isize, isize), a: Amphipod) -> bool {
     amphipod_loc.1 == correct_room_col(a) && amphipod_loc.0 >= 2
 }
 
+#[allow(clippy::only_used_in_recursion)]
 fn search(
     nodes: &HashMap<(isize, isize), NodeType>,
     edges: &HashMap<(isize, isize), Vec<(isize, isize)>>,
@@ -324,8 +332,24 @@ fn out_of_place_amphipods(amphipods: &BTreeMap<(isize, isize), Amphipod>) -> Vec
     out_of_place
 }
 
-pub fn part_1(s: &str) -> isize {
-    let grid: Vec<Vec<char>> = s.lines().map(|l| l.chars().collect()).collect();
+pub fn parse(s: &str) -> Result<Vec<String>, ParseError> {
+    let mut lines = parse::lines(s);
+    let first = lines.expect()?;
+    std::iter::once(first)
+        .chain(lines)
+        .map(|line| {
+            for (c, at) in line.chars() {
+                if !".ABCD #".contains(c) {
+                    return Err(line.error(at, ErrorKind::UnexpectedChar));
+                }
+            }
+            Ok(line.text.to_owned())
+        })
+        .collect()
+}
+
+fn least_energy(rows: &[String], tracer: &mut dyn Tracer) -> isize {
+    let grid: Vec<Vec<char>> = rows.iter().map(|l| l.chars().collect()).collect();
 
     let mut nodes = HashMap::new();
     let mut amphipods = BTreeMap::new();
@@ -376,7 +400,9 @@ pub fn part_1(s: &str) -> isize {
         edges.insert((i, j), e);
     }
 
-    print_map(&nodes, &amphipods);
+    if tracer.enabled() {
+        trace!(tracer, "{}", render_map(&nodes, &amphipods));
+    }
 
     // Explore every possible action
 
@@ -384,36 +410,101 @@ pub fn part_1(s: &str) -> isize {
     search(&nodes, &edges, &amphipods, 0, &mut cache).unwrap()
 }
 
-pub fn part_2(s: &str) -> isize {
-    part_1(s)
+// Part two is played on the unfolded diagram. The puzzle input is the folded
+// one, so the two hidden rows are inserted here, which lets the same input
+// file serve both parts; a diagram that's already unfolded (seven lines) is
+// solved as given.
+fn least_energy_unfolded(rows: &[String], tracer: &mut dyn Tracer) -> isize {
+    if rows.len() == 5 {
+        let mut unfolded = rows.to_vec();
+        unfolded.insert(3, "  #D#C#B#A#".to_owned());
+        unfolded.insert(4, "  #D#B#A#C#".to_owned());
+        least_energy(&unfolded, tracer)
+    } else {
+        least_energy(rows, tracer)
+    }
+}
+
+pub fn part_1(s: &str) -> Result<isize, ParseError> {
+    Ok(least_energy(&parse(s)?, &mut Silent))
+}
+
+pub fn part_2(s: &str) -> Result<isize, ParseError> {
+    Ok(least_energy_unfolded(&parse(s)?, &mut Silent))
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: usize = 23;
+
+    type Input = Vec<String>;
+    type Answer1 = isize;
+    type Answer2 = isize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        least_energy(input, &mut Silent)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(least_energy_unfolded(input, &mut Silent))
+    }
+
+    fn part_1_traced(input: &Self::Input, tracer: &mut dyn Tracer) -> Self::Answer1 {
+        least_energy(input, tracer)
+    }
+
+    fn part_2_traced(input: &Self::Input, tracer: &mut dyn Tracer) -> Option<Self::Answer2> {
+        Some(least_energy_unfolded(input, tracer))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2, Day23};
+    use crate::solution::Solution;
 
     #[test]
     fn test_day_23_example_part_1() {
-        assert_eq!(part_1(EXAMPLE4), 9015);
-        assert_eq!(part_1(EXAMPLE), 12521);
+        assert_eq!(part_1(EXAMPLE4).unwrap(), 9015);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 12521);
     }
 
     #[test]
     fn test_day_23_part_1() {
-        assert_eq!(part_1(include_str!("input/day_23.txt")), 10411);
+        assert_eq!(part_1(include_str!("input/day_23.txt")).unwrap(), 10411);
     }
 
     #[test]
     fn test_day_23_example_part_2() {
-        assert_eq!(part_2(EXAMPLE2), 44169);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 44169);
+        assert_eq!(part_2(EXAMPLE2).unwrap(), 44169);
     }
 
     #[test]
     fn test_day_23_part_2() {
-        let answer = part_2(include_str!("input/day_23_2.txt"));
+        let answer = part_2(include_str!("input/day_23_2.txt")).unwrap();
         assert_eq!(answer, 46721);
     }
 
+    #[test]
+    fn test_day_23_trace() {
+        let mut buffer: Vec<String> = vec![];
+        let input = parse(EXAMPLE4).unwrap();
+        assert_eq!(Day23::part_1_traced(&input, &mut buffer), 9015);
+        assert_eq!(buffer, vec!["..x.x.x.x..\n  A B C A  \n  D B C D  "]);
+    }
+
+    #[test]
+    fn test_day_23_parse_error() {
+        let e = parse("#############\n#...........#\n###B#C#E#D###").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 8, "E"));
+    }
+
     const EXAMPLE: &str = r#"#############
 #...........#
 ###B#C#B#D###
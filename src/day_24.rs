@@ -92,6 +92,11 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt;
 
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+use crate::trace;
+use crate::trace::{Silent, Tracer};
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
 pub struct Registers {
     w: isize,
@@ -129,13 +134,13 @@ pub enum Register {
 }
 
 impl Register {
-    fn new(s: &str) -> Register {
+    fn new(line: &parse::Line<'_>, s: &str) -> Result<Register, ParseError> {
         match s {
-            "w" => Register::W,
-            "x" => Register::X,
-            "y" => Register::Y,
-            "z" => Register::Z,
-            _ => unreachable!(),
+            "w" => Ok(Register::W),
+            "x" => Ok(Register::X),
+            "y" => Ok(Register::Y),
+            "z" => Ok(Register::Z),
+            _ => Err(line.error(s, ErrorKind::UnknownKeyword)),
         }
     }
 }
@@ -147,10 +152,10 @@ pub enum Arg {
 }
 
 impl Arg {
-    fn new(s: &str) -> Arg {
+    fn new(line: &parse::Line<'_>, s: &str) -> Result<Arg, ParseError> {
         match s {
-            "w" | "x" | "y" | "z" => Arg::Register(Register::new(s)),
-            s => Arg::Immediate(s.parse().unwrap()),
+            "w" | "x" | "y" | "z" => Ok(Arg::Register(Register::new(line, s)?)),
+            s => Ok(Arg::Immediate(line.number(s)?)),
         }
     }
 }
@@ -224,53 +229,42 @@ pub fn eval(
     r
 }
 
-pub fn parse_prog(s: &str) -> Vec<Instruction> {
+pub fn parse(s: &str) -> Result<Vec<Instruction>, ParseError> {
     let mut prog = vec![];
-    for line in s.lines() {
-        let (cmd, args) = line.split_once(' ').unwrap();
+    for line in parse::lines(s) {
+        let (cmd, args) = line.split_once(line.text, " ")?;
+        let binary = || -> Result<(Register, Arg), ParseError> {
+            let (a, b) = line.split_once(args, " ")?;
+            Ok((Register::new(&line, a)?, Arg::new(&line, b)?))
+        };
         prog.push(match cmd {
             "inp" => Instruction::Inp {
-                a: Register::new(args),
+                a: Register::new(&line, args)?,
             },
             "add" => {
-                let (a, b) = args.split_once(' ').unwrap();
-                Instruction::Add {
-                    a: Register::new(a),
-                    b: Arg::new(b),
-                }
+                let (a, b) = binary()?;
+                Instruction::Add { a, b }
             }
             "mul" => {
-                let (a, b) = args.split_once(' ').unwrap();
-                Instruction::Mul {
-                    a: Register::new(a),
-                    b: Arg::new(b),
-                }
+                let (a, b) = binary()?;
+                Instruction::Mul { a, b }
             }
             "div" => {
-                let (a, b) = args.split_once(' ').unwrap();
-                Instruction::Div {
-                    a: Register::new(a),
-                    b: Arg::new(b),
-                }
+                let (a, b) = binary()?;
+                Instruction::Div { a, b }
             }
             "mod" => {
-                let (a, b) = args.split_once(' ').unwrap();
-                Instruction::Mod {
-                    a: Register::new(a),
-                    b: Arg::new(b),
-                }
+                let (a, b) = binary()?;
+                Instruction::Mod { a, b }
             }
             "eql" => {
-                let (a, b) = args.split_once(' ').unwrap();
-                Instruction::Eql {
-                    a: Register::new(a),
-                    b: Arg::new(b),
-                }
+                let (a, b) = binary()?;
+                Instruction::Eql { a, b }
             }
-            _ => unreachable!(),
+            _ => return Err(line.error(cmd, ErrorKind::UnknownKeyword)),
         });
     }
-    prog
+    Ok(prog)
 }
 
 #[derive(Clone, Eq, PartialEq, Hash)]
@@ -607,6 +601,7 @@ pub fn solve(
     mut analysis: Analysis,
     idx: usize,
     parent: Vec<(Expr, isize)>,
+    tracer: &mut dyn Tracer,
 ) -> Option<Vec<(Expr, isize)>> {
     if idx == 15 {
         // This is the end!
@@ -617,8 +612,7 @@ pub fn solve(
         };
     }
 
-    eprint!("Z[{}]\t= ", idx);
-    eprintln!("{:?}", analysis.get_z_value(idx));
+    trace!(tracer, "Z[{}]\t= {:?}", idx, analysis.get_z_value(idx));
 
     loop {
         let mut did_something = false;
@@ -634,7 +628,7 @@ pub fn solve(
             let n = z.replace(&from, to);
             if n != *z {
                 did_something = true;
-                println!("\t= {:?}", n);
+                trace!(tracer, "\t= {:?}", n);
                 analysis.set_z_value(idx, n.clone());
             }
         }
@@ -646,7 +640,7 @@ pub fn solve(
             let n = z.replace(&from, to);
             if n != *z {
                 did_something = true;
-                eprintln!("\t= {:?}", n);
+                trace!(tracer, "\t= {:?}", n);
                 analysis.set_z_value(idx, n.clone());
             }
         }
@@ -656,7 +650,7 @@ pub fn solve(
             let n = z.distribute_divs();
             if n != *z {
                 did_something = true;
-                eprintln!("\t= {:?}", n);
+                trace!(tracer, "\t= {:?}", n);
                 analysis.set_z_value(idx, n.clone());
             } else {
                 break;
@@ -681,7 +675,7 @@ pub fn solve(
 
     if eq_exprs.is_empty() {
         // Just recurse to the right
-        return solve(analysis, idx + 1, parent);
+        return solve(analysis, idx + 1, parent, tracer);
     }
     let eq_exprs = eq_exprs.into_iter().collect::<Vec<_>>();
     let mut routes = vec![];
@@ -705,20 +699,19 @@ pub fn solve(
     }
 
     for (a, p) in routes {
-        if let Some(x) = solve(a, idx + 1, p) {
+        if let Some(x) = solve(a, idx + 1, p, tracer) {
             return Some(x);
         }
     }
     None
 }
 
-fn common(s: &str) -> (Vec<Instruction>, HashMap<usize, Vec<isize>>) {
-    let prog = parse_prog(s);
-    let analysis = parse_prog_2(&prog);
-    let soln = solve(analysis, 0, vec![]).unwrap();
+fn common(prog: &[Instruction], tracer: &mut dyn Tracer) -> HashMap<usize, Vec<isize>> {
+    let analysis = parse_prog_2(prog);
+    let soln = solve(analysis, 0, vec![], tracer).unwrap();
     let mut viable: HashMap<usize, Vec<isize>> = HashMap::new();
     for (eq, v) in soln {
-        eprintln!("solving for {:?} == {:?}", eq, v);
+        trace!(tracer, "solving for {:?} == {:?}", eq, v);
         let inputs = eq.inputs();
         let mut m = HashMap::new();
         for i in &inputs {
@@ -749,25 +742,25 @@ fn common(s: &str) -> (Vec<Instruction>, HashMap<usize, Vec<isize>>) {
             }
         }
     }
-    eprintln!();
-    eprintln!("viable mappings");
+    trace!(tracer, "");
+    trace!(tracer, "viable mappings");
     for i in 0..14 {
-        eprintln!("IN[{}]: {:?}", i, viable[&i]);
+        trace!(tracer, "IN[{}]: {:?}", i, viable[&i]);
     }
 
-    (prog, viable)
+    viable
 }
 
-pub fn part_1(s: &str) -> isize {
-    let (prog, viable) = common(s);
+fn smallest_model_number(prog: &[Instruction], tracer: &mut dyn Tracer) -> isize {
+    let viable = common(prog, tracer);
 
     let mut min_v = isize::MAX;
 
-    let mut idxes = vec![0; 14];
+    let mut idxes = [0; 14];
 
     loop {
         let iter = (0..14).map(|i| viable[&i][idxes[i]]);
-        let registers = eval(iter.clone(), &prog, Registers::default());
+        let registers = eval(iter.clone(), prog, Registers::default());
 
         if registers.z == 0 {
             let v = iter.reduce(|acc, v| acc * 10 + v).unwrap();
@@ -794,19 +787,19 @@ pub fn part_1(s: &str) -> isize {
     min_v
 }
 
-pub fn part_2(s: &str) -> isize {
-    let (prog, viable) = common(s);
+fn largest_model_number(prog: &[Instruction], tracer: &mut dyn Tracer) -> isize {
+    let viable = common(prog, tracer);
 
     let mut max_v = isize::MIN;
 
-    let mut idxes = vec![0; 14];
+    let mut idxes = [0; 14];
     for i in 0..idxes.len() {
         idxes[i] = viable[&i].len() - 1;
     }
 
     loop {
         let iter = (0..14).map(|i| viable[&i][idxes[i]]);
-        let registers = eval(iter.clone(), &prog, Registers::default());
+        let registers = eval(iter.clone(), prog, Registers::default());
 
         if registers.z == 0 {
             let v = iter.reduce(|acc, v| acc * 10 + v).unwrap();
@@ -833,18 +826,73 @@ pub fn part_2(s: &str) -> isize {
     max_v
 }
 
+pub fn part_1(s: &str) -> Result<isize, ParseError> {
+    Ok(smallest_model_number(&parse(s)?, &mut Silent))
+}
+
+pub fn part_2(s: &str) -> Result<isize, ParseError> {
+    Ok(largest_model_number(&parse(s)?, &mut Silent))
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: usize = 24;
+
+    type Input = Vec<Instruction>;
+    type Answer1 = isize;
+    type Answer2 = isize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        smallest_model_number(input, &mut Silent)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(largest_model_number(input, &mut Silent))
+    }
+
+    fn part_1_traced(input: &Self::Input, tracer: &mut dyn Tracer) -> Self::Answer1 {
+        smallest_model_number(input, tracer)
+    }
+
+    fn part_2_traced(input: &Self::Input, tracer: &mut dyn Tracer) -> Option<Self::Answer2> {
+        Some(largest_model_number(input, tracer))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     #[test]
     fn test_day_24_part_1() {
-        assert_eq!(part_1(include_str!("input/day_24.txt")), 11717131211195);
+        assert_eq!(
+            part_1(include_str!("input/day_24.txt")).unwrap(),
+            11717131211195
+        );
     }
 
     #[test]
     fn test_day_24_part_2() {
-        let answer = part_2(include_str!("input/day_24.txt"));
+        let answer = part_2(include_str!("input/day_24.txt")).unwrap();
         assert_eq!(answer, 51939397989999);
     }
+
+    #[test]
+    fn test_day_24_parse_error() {
+        let e = parse("inp w\nadd x 1\nmul q 0").unwrap_err();
+        assert_eq!(
+            (e.line, e.column, e.kind),
+            (3, 5, ErrorKind::UnknownKeyword)
+        );
+        let e = parse("inp w\nsub x 1").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "sub"));
+        let e = parse("inp w\nadd x 1o").unwrap_err();
+        assert_eq!((e.column, e.kind), (7, ErrorKind::InvalidNumber));
+    }
 }
@@ -301,93 +301,95 @@
 // Find somewhere safe to land your submarine. What is the first step on which no sea cucumbers
 // move?
 
-pub fn print_grid(grid: &[Vec<char>]) {
-    for row in grid {
-        for c in row {
-            eprint!("{}", c);
-        }
-        eprintln!();
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+
+pub fn print_grid(grid: &Grid<char>) {
+    eprintln!("{}", grid);
+}
+
+pub fn parse(s: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(s, |c| Some(c).filter(|c| ".>v".contains(*c)))
+}
+
+// Moves every cucumber in the herd that faces `delta` and has room to do so,
+// returning how many moved.
+fn move_herd(grid: &mut Grid<char>, herd: char, delta: (isize, isize)) -> usize {
+    let moves = grid
+        .iter()
+        .filter(|(_, c)| **c == herd)
+        .map(|(pos, _)| (pos, grid.wrapping_offset(pos, delta)))
+        .filter(|(_, next)| grid[*next] == '.')
+        .collect::<Vec<_>>();
+
+    // update the grid
+    for (prev, next) in &moves {
+        grid[*prev] = '.';
+        grid[*next] = herd;
     }
+    moves.len()
 }
 
-pub fn part_1(s: &str) -> usize {
-    let mut grid: Vec<Vec<char>> = s.lines().map(|l| l.chars().collect()).collect();
-
-    let h = grid.len();
-    let w = grid[0].len();
-
-    for step in 0.. {
-        let mut moves = vec![];
-
-        for (i, row) in grid.iter().enumerate() {
-            for (j, c) in row.iter().enumerate() {
-                match c {
-                    '.' | 'v' => (),
-                    '>' => {
-                        let next_pos = (i, (j + 1) % w);
-                        if grid[next_pos.0][next_pos.1] == '.' {
-                            moves.push(((i, j), next_pos, '>'));
-                        }
-                    }
-                    _ => unreachable!(),
-                }
-            }
-        }
-        let mut num_moves = moves.len();
-
-        // update the grid
-        for (prev, _, _) in &moves {
-            grid[prev.0][prev.1] = '.';
-        }
-        for (_, next, c) in moves {
-            grid[next.0][next.1] = c;
-        }
-        let mut moves = vec![];
-
-        for (i, row) in grid.iter().enumerate() {
-            for (j, c) in row.iter().enumerate() {
-                match c {
-                    '.' | '>' => (),
-                    'v' => {
-                        let next_pos = ((i + 1) % h, j);
-                        if grid[next_pos.0][next_pos.1] == '.' {
-                            moves.push(((i, j), next_pos, 'v'));
-                        }
-                    }
-                    _ => unreachable!(),
-                }
-            }
-        }
-        num_moves += moves.len();
-
-        // update the grid
-        for (prev, _, _) in &moves {
-            grid[prev.0][prev.1] = '.';
-        }
-        for (_, next, c) in moves {
-            grid[next.0][next.1] = c;
-        }
-
-        if num_moves == 0 {
-            return step + 1;
-        }
+pub fn step(grid: &mut Grid<char>) -> usize {
+    move_herd(grid, '>', (0, 1)) + move_herd(grid, 'v', (1, 0))
+}
+
+fn first_still_step(grid: &Grid<char>) -> usize {
+    let mut grid = grid.clone();
+    let mut steps = 1;
+    while step(&mut grid) != 0 {
+        steps += 1;
     }
+    steps
+}
 
-    unreachable!()
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(first_still_step(&parse(s)?))
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: usize = 25;
+
+    type Input = Grid<char>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        first_still_step(input)
+    }
+
+    fn part_2(_input: &Self::Input) -> Option<Self::Answer2> {
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::part_1;
+    use super::{parse, part_1};
 
     #[test]
     fn test_day_25_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 58);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 58);
     }
 
     #[test]
     fn test_day_25_part_1() {
-        assert_eq!(part_1(include_str!("input/day_25.txt")), 557);
+        assert_eq!(part_1(include_str!("input/day_25.txt")).unwrap(), 557);
+    }
+
+    #[test]
+    fn test_day_25_parse_error() {
+        let e = parse("v..\n.x>").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+        let e = parse("v..\n.>").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, ".>"));
     }
 
     const EXAMPLE: &str = r#"v...>>.vv>
@@ -121,92 +121,506 @@
 // is the life support rating of the submarine? (Be sure to represent your
 // answer in decimal, not binary.)
 
-use std::collections::BTreeMap;
+use std::cell::Cell;
+use std::fmt;
+use std::io::BufRead;
+use std::iter;
+
+use crate::parse::{self, ErrorKind, ParseError, ReadError};
+use crate::solution::Solution;
+
+/// A row of any number of bits, packed into 64-bit words. Bit 0 is the least
+/// significant one, which is the last character of the row as written.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BitRow {
+    width: usize,
+    words: Vec<u64>,
+}
+
+impl BitRow {
+    pub fn zeros(width: usize) -> Self {
+        BitRow {
+            width,
+            words: vec![0; width.div_ceil(64)],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn bit(&self, idx: usize) -> bool {
+        self.words[idx / 64] >> (idx % 64) & 1 == 1
+    }
 
-pub fn part_1(txt: &str) -> u64 {
-    let mut freq = BTreeMap::new();
+    pub fn set(&mut self, idx: usize, value: bool) {
+        let word = &mut self.words[idx / 64];
+        if value {
+            *word |= 1 << (idx % 64);
+        } else {
+            *word &= !(1 << (idx % 64));
+        }
+    }
 
-    for line in txt.lines() {
-        for (idx, c) in line.chars().rev().enumerate() {
-            match c {
-                '1' => *freq.entry(idx).or_default() += 1isize,
-                '0' => *freq.entry(idx).or_default() -= 1isize,
-                _ => unimplemented!(),
+    /// Flips every bit.
+    pub fn complement(&self) -> BitRow {
+        let mut row = BitRow::zeros(self.width);
+        for idx in 0..self.width {
+            row.set(idx, !self.bit(idx));
+        }
+        row
+    }
+
+    /// The row as a number, if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.words.split_first() {
+            None => Some(0),
+            Some((low, high)) => high.iter().all(|w| *w == 0).then_some(*low),
+        }
+    }
+}
+
+impl fmt::Display for BitRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for idx in (0..self.width).rev() {
+            f.write_str(if self.bit(idx) { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+/// The product of two rows read as unsigned numbers, exact however wide they
+/// are. Shown in decimal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Product {
+    // least significant first, with no zero words at the top
+    words: Vec<u64>,
+}
+
+impl Product {
+    pub fn of(a: &BitRow, b: &BitRow) -> Product {
+        let mut words = vec![0; a.words.len() + b.words.len()];
+        for (i, x) in a.words.iter().enumerate() {
+            let mut carry = 0;
+            for (j, y) in b.words.iter().enumerate() {
+                let t = *x as u128 * *y as u128 + words[i + j] as u128 + carry;
+                words[i + j] = t as u64;
+                carry = t >> 64;
             }
+            words[i + b.words.len()] = carry as u64;
+        }
+        while words.last() == Some(&0) {
+            words.pop();
         }
+        Product { words }
     }
 
-    let num_bits = *freq.keys().max().unwrap();
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.words[..] {
+            [] => Some(0),
+            [w] => Some(w),
+            _ => None,
+        }
+    }
+}
 
-    let gamma: u64 = (0..=num_bits)
-        .map(|idx| match freq.get(&idx).unwrap() {
-            0 => panic!("no most or least common bit"),
-            x if *x > 0 => 1 << idx,
-            _ => 0,
-        })
-        .sum();
+impl PartialEq<u64> for Product {
+    fn eq(&self, other: &u64) -> bool {
+        self.to_u64() == Some(*other)
+    }
+}
 
-    let epsilon: u64 = (0..=num_bits)
-        .filter(|idx| gamma & (1 << idx) == 0)
-        .map(|idx| 1 << idx)
-        .sum();
+impl fmt::Display for Product {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // peel off 19 decimal digits at a time, the most a u64 holds
+        const CHUNK: u128 = 10_000_000_000_000_000_000;
+        let mut words = self.words.clone();
+        let mut chunks = vec![];
+        while !words.is_empty() {
+            let mut rem = 0;
+            for w in words.iter_mut().rev() {
+                let cur = rem << 64 | *w as u128;
+                *w = (cur / CHUNK) as u64;
+                rem = cur % CHUNK;
+            }
+            chunks.push(rem as u64);
+            while words.last() == Some(&0) {
+                words.pop();
+            }
+        }
+        match chunks.split_last() {
+            None => f.write_str("0"),
+            Some((top, rest)) => {
+                write!(f, "{}", top)?;
+                rest.iter().rev().try_for_each(|c| write!(f, "{:019}", c))
+            }
+        }
+    }
+}
 
-    gamma * epsilon
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Report {
+    width: usize,
+    rows: Vec<BitRow>,
 }
 
-pub fn part_2(txt: &str) -> u64 {
-    let mut oxygen = txt
-        .lines()
-        .map(|v| u64::from_str_radix(v, 2).unwrap())
-        .collect::<Vec<_>>();
-    let mut co2 = oxygen.clone();
-    let num_bits = txt.lines().next().unwrap().len();
+// Every row must be as wide as the first one, which sets `width`.
+fn parse_row(line: &parse::Line<'_>, width: &Cell<Option<usize>>) -> Result<BitRow, ParseError> {
+    if width.get().unwrap_or(line.text.len()) != line.text.len() {
+        return Err(line.error(
+            line.text,
+            ErrorKind::Invalid("row width differs from the first row"),
+        ));
+    }
+    width.set(Some(line.text.len()));
 
-    for idx in (0..num_bits).rev() {
-        if oxygen.len() > 1 {
-            let n = oxygen.len();
-            let ones: u64 = oxygen.iter().map(|n| (n & (1 << idx)) >> idx).sum();
-            let zeros = n as u64 - ones;
-            if ones >= zeros {
-                oxygen.retain(|n| (n & (1 << idx)) != 0);
+    let mut row = BitRow::zeros(line.text.len());
+    for (i, (c, at)) in line.chars().enumerate() {
+        row.set(line.text.len() - 1 - i, line.digit(c, at, 2)? == 1);
+    }
+    Ok(row)
+}
+
+pub fn parse(txt: &str) -> Result<Report, ParseError> {
+    let mut lines = parse::lines(txt);
+    let first = lines.expect()?;
+
+    let width = Cell::new(None);
+    let rows = iter::once(first)
+        .chain(lines)
+        .map(|line| parse_row(&line, &width))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Report {
+        width: width.get().unwrap(),
+        rows,
+    })
+}
+
+// How many more rows have each bit set than unset.
+fn bit_balance(rows: impl Iterator<Item = BitRow>) -> Vec<isize> {
+    let mut balance = vec![];
+    for row in rows {
+        balance.resize(row.width(), 0);
+        for (idx, b) in balance.iter_mut().enumerate() {
+            if row.bit(idx) {
+                *b += 1;
             } else {
-                oxygen.retain(|n| (n & (1 << idx)) == 0);
+                *b -= 1;
             }
         }
-        if co2.len() > 1 {
-            let n = co2.len();
-            let ones: u64 = co2.iter().map(|n| (n & (1 << idx)) >> idx).sum();
-            let zeros = n as u64 - ones;
-            if ones >= zeros {
-                co2.retain(|n| (n & (1 << idx)) == 0);
-            } else {
-                co2.retain(|n| (n & (1 << idx)) != 0);
+    }
+    balance
+}
+
+fn rates_from_balance(balance: &[isize], tie: bool) -> (BitRow, BitRow) {
+    let mut gamma = BitRow::zeros(balance.len());
+    for (idx, b) in balance.iter().enumerate() {
+        gamma.set(idx, if *b == 0 { tie } else { *b > 0 });
+    }
+    let epsilon = gamma.complement();
+    (gamma, epsilon)
+}
+
+/// The gamma and epsilon rates, made of the most and least common bits. The
+/// puzzle never ties, so a tied bit counts as a one in gamma, as it does for
+/// the oxygen rating; see `rates_with_ties` to choose.
+pub fn rates(report: &Report) -> (BitRow, BitRow) {
+    rates_with_ties(report, BitCriteria::OXYGEN.tie)
+}
+
+/// Like `rates`, but a tied bit is set to `tie` in gamma (and the opposite
+/// in epsilon).
+pub fn rates_with_ties(report: &Report, tie: bool) -> (BitRow, BitRow) {
+    rates_from_balance(&bit_balance(report.rows.iter().cloned()), tie)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Commonness {
+    Most,
+    Least,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BitOrder {
+    MsbFirst,
+    LsbFirst,
+}
+
+/// How a rating filter decides which rows to keep at each bit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BitCriteria {
+    pub keep: Commonness,
+    /// The value kept when there are as many ones as zeros.
+    pub tie: bool,
+    pub order: BitOrder,
+}
+
+impl BitCriteria {
+    pub const OXYGEN: BitCriteria = BitCriteria {
+        keep: Commonness::Most,
+        tie: true,
+        order: BitOrder::MsbFirst,
+    };
+
+    pub const CO2: BitCriteria = BitCriteria {
+        keep: Commonness::Least,
+        tie: false,
+        order: BitOrder::MsbFirst,
+    };
+
+    fn value(&self, ones: usize, rows: usize) -> bool {
+        let zeros = rows - ones;
+        if ones == zeros {
+            return self.tie;
+        }
+        (ones > zeros) == (self.keep == Commonness::Most)
+    }
+}
+
+/// One step of a rating filter: at bit `bit` (0 being the least significant),
+/// the rows with `value` were kept.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Elimination {
+    pub bit: usize,
+    pub value: bool,
+    pub kept: usize,
+    pub removed: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rating {
+    /// The row left at the end, or `None` if a step kept nothing (say, the
+    /// least common bit when every row agrees). Duplicate rows can leave
+    /// several copies of the same row, which still counts.
+    ///
+    /// Steps where every row agreed, and so nothing was removed, are still
+    /// part of the trace.
+    pub row: Option<BitRow>,
+    pub trace: Vec<Elimination>,
+}
+
+fn bit_order(width: usize, order: BitOrder) -> Box<dyn Iterator<Item = usize>> {
+    match order {
+        BitOrder::MsbFirst => Box::new((0..width).rev()),
+        BitOrder::LsbFirst => Box::new(0..width),
+    }
+}
+
+/// Narrows the rows down one bit at a time, until only one is left. A step
+/// which would keep nothing ends the filter early instead if all the rows
+/// left are the same.
+pub fn filter(report: &Report, criteria: BitCriteria) -> Rating {
+    let mut rows = report.rows.iter().collect::<Vec<_>>();
+    let mut trace = vec![];
+
+    for bit in bit_order(report.width, criteria.order) {
+        if rows.len() <= 1 {
+            break;
+        }
+        let before = rows.len();
+        let ones = rows.iter().filter(|r| r.bit(bit)).count();
+        let value = criteria.value(ones, before);
+        let kept = if value { ones } else { before - ones };
+        if kept == 0 && rows.iter().all(|r| *r == rows[0]) {
+            break;
+        }
+        rows.retain(|r| r.bit(bit) == value);
+        trace.push(Elimination {
+            bit,
+            value,
+            kept,
+            removed: before - kept,
+        });
+    }
+
+    Rating {
+        row: rows.first().map(|r| (*r).clone()),
+        trace,
+    }
+}
+
+/// The report turned on its side: one bitset per bit position, holding that
+/// bit of every row. Counting the ones among the rows still in the running
+/// is then a popcount over words rather than a pass over rows.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitPlanes {
+    rows: usize,
+    width: usize,
+    planes: Vec<Vec<u64>>,
+}
+
+impl BitPlanes {
+    pub fn new(report: &Report) -> Self {
+        let words = report.rows.len().div_ceil(64);
+        let mut planes = vec![vec![0; words]; report.width];
+        for (r, row) in report.rows.iter().enumerate() {
+            for (w, &word) in row.words.iter().enumerate() {
+                let mut word = word;
+                while word != 0 {
+                    let bit = w * 64 + word.trailing_zeros() as usize;
+                    planes[bit][r / 64] |= 1 << (r % 64);
+                    word &= word - 1;
+                }
+            }
+        }
+        BitPlanes {
+            rows: report.rows.len(),
+            width: report.width,
+            planes,
+        }
+    }
+
+    fn row(&self, r: usize) -> BitRow {
+        let mut row = BitRow::zeros(self.width);
+        for (bit, plane) in self.planes.iter().enumerate() {
+            row.set(bit, plane[r / 64] >> (r % 64) & 1 == 1);
+        }
+        row
+    }
+
+    fn ones(plane: &[u64], alive: &[u64], words: &[usize]) -> usize {
+        words
+            .iter()
+            .map(|&w| (plane[w] & alive[w]).count_ones() as usize)
+            .sum()
+    }
+
+    /// Gives the same answer as `filter`. Only the words which still have a
+    /// row in them are looked at, so later steps get cheaper as rows drop
+    /// out.
+    pub fn filter(&self, criteria: BitCriteria) -> Rating {
+        let mut alive = vec![u64::MAX; self.rows.div_ceil(64)];
+        if !self.rows.is_multiple_of(64) {
+            *alive.last_mut().unwrap() = (1 << (self.rows % 64)) - 1;
+        }
+        let mut words = (0..alive.len()).collect::<Vec<_>>();
+        let mut count = self.rows;
+        let mut trace = vec![];
+
+        for bit in bit_order(self.width, criteria.order) {
+            if count <= 1 {
+                break;
             }
+            let plane = &self.planes[bit];
+            let ones = Self::ones(plane, &alive, &words);
+            let value = criteria.value(ones, count);
+            let kept = if value { ones } else { count - ones };
+            if kept == 0
+                && self.planes.iter().all(|p| {
+                    let ones = Self::ones(p, &alive, &words);
+                    ones == 0 || ones == count
+                })
+            {
+                break;
+            }
+
+            for &w in &words {
+                alive[w] &= if value { plane[w] } else { !plane[w] };
+            }
+            words.retain(|&w| alive[w] != 0);
+            trace.push(Elimination {
+                bit,
+                value,
+                kept,
+                removed: count - kept,
+            });
+            count = kept;
+        }
+
+        let first = words
+            .first()
+            .map(|&w| w * 64 + alive[w].trailing_zeros() as usize);
+        Rating {
+            row: first.map(|r| self.row(r)),
+            trace,
         }
     }
+}
+
+/// The oxygen generator and CO2 scrubber ratings.
+pub fn ratings(report: &Report) -> (BitRow, BitRow) {
+    let planes = BitPlanes::new(report);
+    let rating = |criteria| {
+        planes
+            .filter(criteria)
+            .row
+            .expect("no rows survive the filter")
+    };
+    (rating(BitCriteria::OXYGEN), rating(BitCriteria::CO2))
+}
+
+fn power_consumption(report: &Report) -> Product {
+    let (gamma, epsilon) = rates(report);
+    Product::of(&gamma, &epsilon)
+}
+
+fn life_support_rating(report: &Report) -> Product {
+    let (oxygen, co2) = ratings(report);
+    Product::of(&oxygen, &co2)
+}
+
+pub fn part_1(txt: &str) -> Result<Product, ParseError> {
+    Ok(power_consumption(&parse(txt)?))
+}
+
+pub fn part_2(txt: &str) -> Result<Product, ParseError> {
+    Ok(life_support_rating(&parse(txt)?))
+}
+
+pub fn read_part_1(r: impl BufRead) -> Result<Product, ReadError> {
+    let width = Cell::new(None);
+    let rows = parse::records(r, |line| parse_row(line, &width));
+    let balance = parse::process_records(rows, |rows| bit_balance(rows))?;
+    if width.get().is_none() {
+        return Err(ParseError::end_of_input(1).into());
+    }
+    let (gamma, epsilon) = rates_from_balance(&balance, BitCriteria::OXYGEN.tie);
+    Ok(Product::of(&gamma, &epsilon))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: usize = 3;
+
+    type Input = Report;
+    type Answer1 = Product;
+    type Answer2 = Product;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
 
-    assert_eq!(oxygen.len(), 1);
-    assert_eq!(co2.len(), 1);
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        power_consumption(input)
+    }
 
-    oxygen[0] * co2[0]
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(life_support_rating(input))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{
+        filter, parse, part_1, part_2, rates, rates_with_ties, ratings, read_part_1, BitCriteria,
+        BitOrder, BitPlanes, BitRow, Commonness, Product,
+    };
+    use crate::generate::{self, Rng};
 
     #[test]
     fn test_day_3_example_part1() {
         let input =
             "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
 
-        assert_eq!(part_1(input), 198);
+        assert_eq!(part_1(input).unwrap(), 198);
     }
 
     #[test]
     fn test_day_3_part_1() {
         let input = include_str!("input/day_3.txt");
-        assert_eq!(part_1(input), 3320834);
+        assert_eq!(part_1(input).unwrap(), 3320834);
     }
 
     #[test]
@@ -214,12 +628,160 @@ mod tests {
         let input =
             "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
 
-        assert_eq!(part_2(input), 230);
+        assert_eq!(part_2(input).unwrap(), 230);
     }
 
     #[test]
     fn test_day_3_part_2() {
         let input = include_str!("input/day_3.txt");
-        assert_eq!(part_2(input), 4481199);
+        assert_eq!(part_2(input).unwrap(), 4481199);
+    }
+
+    #[test]
+    fn test_day_3_read() {
+        let input = include_str!("input/day_3.txt");
+        assert_eq!(read_part_1(input.as_bytes()).unwrap(), 3320834);
+        assert!(read_part_1("00100\n1111\n".as_bytes()).is_err());
+        assert!(read_part_1("".as_bytes()).is_err());
+        // a tied bit is a one in gamma
+        assert_eq!(part_1("00\n11").unwrap(), 0);
+        assert_eq!(part_1("00\n01\n10\n01").unwrap(), 2);
+        assert_eq!(read_part_1("00\n01\n10\n01".as_bytes()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_day_3_parse_error() {
+        let e = parse("00100\n11120\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 4, "2"));
+        let e = parse("00100\n1111\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "1111"));
+    }
+
+    // Works on the rows as text, one character at a time.
+    fn reference_rating(rows: &[&str], most_common: bool) -> String {
+        let mut rows = rows.to_vec();
+        let mut idx = 0;
+        while rows.len() > 1 {
+            let ones = rows.iter().filter(|r| r.as_bytes()[idx] == b'1').count();
+            let keep = if (ones * 2 >= rows.len()) == most_common {
+                b'1'
+            } else {
+                b'0'
+            };
+            rows.retain(|r| r.as_bytes()[idx] == keep);
+            idx += 1;
+        }
+        rows[0].to_owned()
+    }
+
+    #[test]
+    fn test_day_3_wide_rows() {
+        let input = generate::bit_report(&mut Rng::new(3), 101, 200);
+        let rows = input.lines().collect::<Vec<_>>();
+        assert_eq!(rows[0].len(), 200);
+        let report = parse(&input).unwrap();
+
+        let (gamma, epsilon) = rates(&report);
+        let expected = (0..200)
+            .map(|i| {
+                let ones = rows.iter().filter(|r| r.as_bytes()[i] == b'1').count();
+                if ones * 2 > rows.len() {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect::<String>();
+        assert_eq!(gamma.to_string(), expected);
+        assert_eq!(gamma.complement(), epsilon);
+        assert_eq!(gamma.to_u64(), None);
+
+        let (oxygen, co2) = ratings(&report);
+        assert_eq!(oxygen.to_string(), reference_rating(&rows, true));
+        assert_eq!(co2.to_string(), reference_rating(&rows, false));
+
+        // gamma is 2^69 and epsilon one less
+        let wide = format!(
+            "{}\n1{}\n{}",
+            "1".repeat(70),
+            "0".repeat(69),
+            "0".repeat(70)
+        );
+        assert_eq!(
+            part_1(&wide).unwrap().to_string(),
+            "348449143727040986585905302199771942879232"
+        );
+        let row = parse(&format!("1{}", "0".repeat(64))).unwrap().rows[0].clone();
+        assert_eq!(
+            Product::of(&row, &row).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(Product::of(&row, &BitRow::zeros(3)), 0);
+    }
+
+    #[test]
+    fn test_day_3_bit_criteria() {
+        let input =
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
+        let report = parse(input).unwrap();
+
+        let oxygen = filter(&report, BitCriteria::OXYGEN);
+        assert_eq!(oxygen.row.unwrap().to_u64(), Some(23));
+        assert_eq!(
+            oxygen.trace.iter().map(|e| e.kept).collect::<Vec<_>>(),
+            vec![7, 4, 3, 2, 1]
+        );
+        assert_eq!((oxygen.trace[4].bit, oxygen.trace[4].value), (0, true));
+
+        let lsb_first = BitCriteria {
+            keep: Commonness::Most,
+            tie: false,
+            order: BitOrder::LsbFirst,
+        };
+        let rating = filter(&report, lsb_first);
+        assert_eq!(rating.row.unwrap().to_string(), "00010");
+        assert_eq!(rating.trace[0].bit, 0);
+
+        // every row has bit 0 clear, so "least common" keeps nothing there
+        let report = parse("110\n100").unwrap();
+        let rating = filter(
+            &report,
+            BitCriteria {
+                order: BitOrder::LsbFirst,
+                ..BitCriteria::CO2
+            },
+        );
+        assert_eq!(rating.row, None);
+        assert_eq!(rating.trace[0].removed, 2);
+
+        let (gamma, epsilon) = rates_with_ties(&report, false);
+        assert_eq!(
+            (gamma.to_string(), epsilon.to_string()),
+            ("100".to_owned(), "011".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_day_3_bit_planes() {
+        let mut rng = Rng::new(19);
+        // a wide report spanning several words either way, and a narrow one
+        // full of duplicates
+        for (count, width) in [(200, 70), (150, 3)] {
+            let mut input = String::new();
+            for _ in 0..count {
+                input.extend((0..width).map(|_| if rng.chance(1, 2) { '1' } else { '0' }));
+                input.push('\n');
+            }
+            let report = parse(&input).unwrap();
+            let planes = BitPlanes::new(&report);
+            for keep in [Commonness::Most, Commonness::Least] {
+                for tie in [false, true] {
+                    for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+                        let criteria = BitCriteria { keep, tie, order };
+                        assert_eq!(planes.filter(criteria), filter(&report, criteria));
+                    }
+                }
+            }
+        }
     }
 }
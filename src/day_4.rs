@@ -1,6 +1,7 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
-///
-/// --- Day 4: Giant Squid ---
+
+// --- Day 4: Giant Squid ---
 // You're already almost 1.5km (almost a mile) below the surface of the ocean,
 // already so deep that you can't see any sunlight. What you can see, however,
 // is a giant squid that has attached itself to the outside of your submarine.
@@ -94,151 +95,843 @@ use std::fmt;
 
 // Figure out which board will win last. Once it wins, what would its final
 // score be?
-use std::str::FromStr;
+use crate::generate::Rng;
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
 
-pub fn parse_board<'a, I: Iterator<Item = &'a str>>(mut s: I) -> (I, Board) {
-    let mut board = [[0u8; 5]; 5];
+pub type Number = u32;
+
+/// A set of cells on a board, numbered row by row from the top left.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Mask {
+    cells: usize,
+    words: Vec<u64>,
+}
+
+impl Mask {
+    /// An empty mask for a board of `cells` cells.
+    pub fn new(cells: usize) -> Self {
+        Mask {
+            cells,
+            words: vec![0; cells.div_ceil(64)],
+        }
+    }
 
-    for r in &mut board {
-        let l = s.next().unwrap();
-        let mut l_it = l.split_whitespace();
+    /// How many cells the board this mask was made for has.
+    pub fn board_cells(&self) -> usize {
+        self.cells
+    }
 
-        for c in &mut *r {
-            *c = l_it.next().unwrap().parse().unwrap();
+    /// A mask for a `size` by `size` board with the given `(row, column)`
+    /// cells set.
+    pub fn from_cells(size: usize, cells: &[(usize, usize)]) -> Self {
+        let mut mask = Mask::new(size * size);
+        for &(r, c) in cells {
+            assert!(r < size && c < size, "cell is off the board");
+            mask.set(r * size + c);
         }
+        mask
+    }
+
+    pub fn set(&mut self, cell: usize) {
+        assert!(cell < self.cells, "cell is off the board");
+        self.words[cell / 64] |= 1 << (cell % 64);
+    }
+
+    pub fn get(&self, cell: usize) -> bool {
+        self.words[cell / 64] >> (cell % 64) & 1 == 1
+    }
+
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
     }
 
-    (s, Board::new(board))
+    pub fn cells(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.cells).filter(move |c| self.get(*c))
+    }
+
+    /// Whether every cell of `self` is also in `other`.
+    pub fn is_subset(&self, other: &Mask) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(a, b)| a & !b == 0)
+    }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub struct Board {
-    board: [[u8; 5]; 5],
-    hits: u32,
+impl fmt::Debug for Mask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.cells()).finish()
+    }
 }
 
-impl Board {
-    pub fn new(board: [[u8; 5]; 5]) -> Self {
-        Self { board, hits: 0 }
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    Rows,
+    Columns,
+    /// Both diagonals.
+    Diagonals,
+    FourCorners,
+    Blackout,
+    Custom(Mask),
+}
+
+/// The ways to win on a board of one size. A board wins once every cell of
+/// any one of them is marked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WinPatterns {
+    size: usize,
+    masks: Vec<Mask>,
+}
+
+impl WinPatterns {
+    pub fn new(size: usize) -> Self {
+        WinPatterns {
+            size,
+            masks: vec![],
+        }
+    }
+
+    /// Rows and columns, as in the puzzle.
+    pub fn standard(size: usize) -> Self {
+        WinPatterns::new(size)
+            .with(Pattern::Rows)
+            .with(Pattern::Columns)
     }
 
-    pub fn hit(&mut self, v: u8) -> bool {
-        for i in 0..5 {
-            for j in 0..5 {
-                if self.board[i][j] == v {
-                    let offset = i * 5 + j;
-                    self.hits |= 1 << offset;
-                    return true;
+    pub fn with(mut self, pattern: Pattern) -> Self {
+        let n = self.size;
+        let cells = |f: &dyn Fn(usize) -> (usize, usize)| (0..n).map(f).collect::<Vec<_>>();
+        match pattern {
+            Pattern::Rows => {
+                for r in 0..n {
+                    self.masks.push(Mask::from_cells(n, &cells(&|c| (r, c))));
                 }
             }
+            Pattern::Columns => {
+                for c in 0..n {
+                    self.masks.push(Mask::from_cells(n, &cells(&|r| (r, c))));
+                }
+            }
+            Pattern::Diagonals => {
+                self.masks.push(Mask::from_cells(n, &cells(&|i| (i, i))));
+                self.masks
+                    .push(Mask::from_cells(n, &cells(&|i| (i, n - 1 - i))));
+            }
+            Pattern::FourCorners => {
+                let corners = [(0, 0), (0, n - 1), (n - 1, 0), (n - 1, n - 1)];
+                self.masks.push(Mask::from_cells(n, &corners));
+            }
+            Pattern::Blackout => {
+                let all = (0..n * n).map(|i| (i / n, i % n)).collect::<Vec<_>>();
+                self.masks.push(Mask::from_cells(n, &all));
+            }
+            Pattern::Custom(mask) => {
+                assert_eq!(mask.board_cells(), n * n, "mask is for another board size");
+                // every board would have won before the first draw
+                assert!(mask.count() > 0, "mask is empty");
+                self.masks.push(mask);
+            }
         }
-        false
+        self
     }
 
-    pub fn sum_unmarked(&self) -> u64 {
-        let mut sum = 0u64;
+    pub fn size(&self) -> usize {
+        self.size
+    }
 
-        for i in 0..5 {
-            for j in 0..5 {
-                let offset = i * 5 + j;
-                if self.hits & (1 << offset) == 0 {
-                    sum += self.board[i][j] as u64;
-                }
+    pub fn masks(&self) -> &[Mask] {
+        &self.masks
+    }
+
+    pub fn is_win(&self, hits: &Mask) -> bool {
+        self.masks.iter().any(|m| m.is_subset(hits))
+    }
+
+    /// Like `is_win`, but only looks at the patterns through `cell`, for
+    /// when that's the only cell which changed.
+    pub fn is_win_at(&self, hits: &Mask, cell: usize) -> bool {
+        self.masks.iter().any(|m| m.get(cell) && m.is_subset(hits))
+    }
+}
+
+// The board's size is set by how many numbers are on its first row.
+pub fn parse_board(lines: &mut parse::Lines<'_>) -> Result<Board, ParseError> {
+    let first = lines.expect()?;
+    let mut cells = first
+        .text
+        .split_whitespace()
+        .map(|v| first.number(v))
+        .collect::<Result<Vec<Number>, _>>()?;
+    let size = cells.len();
+    if size == 0 {
+        return Err(first.missing());
+    }
+
+    for _ in 1..size {
+        let l = lines.expect()?;
+        let mut l_it = l.text.split_whitespace();
+        for _ in 0..size {
+            cells.push(l.number(l.field(&mut l_it)?)?);
+        }
+        if let Some(extra) = l_it.next() {
+            return Err(l.error(
+                extra,
+                ErrorKind::Invalid("board rows must all be the same length"),
+            ));
+        }
+    }
+
+    Ok(Board::new(size, cells))
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Board {
+    size: usize,
+    cells: Vec<Number>,
+    hits: Mask,
+}
+
+impl Board {
+    /// Builds a `size` by `size` board from its numbers, row by row.
+    pub fn new(size: usize, cells: Vec<Number>) -> Self {
+        assert_eq!(cells.len(), size * size, "board isn't square");
+        Self {
+            size,
+            cells,
+            hits: Mask::new(size * size),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn cells(&self) -> &[Number] {
+        &self.cells
+    }
+
+    pub fn hits(&self) -> &Mask {
+        &self.hits
+    }
+
+    pub fn hit(&mut self, v: Number) -> bool {
+        match self.cells.iter().position(|c| *c == v) {
+            Some(offset) => {
+                self.hits.set(offset);
+                true
             }
+            None => false,
         }
+    }
 
-        sum
+    pub fn mark(&mut self, cell: usize) {
+        self.hits.set(cell);
     }
 
+    pub fn sum_unmarked(&self) -> u64 {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(offset, _)| !self.hits.get(*offset))
+            .map(|(_, v)| *v as u64)
+            .sum()
+    }
+
+    /// Whether any row or column is complete.
     pub fn is_bingo(&self) -> bool {
-        // Horizontals
-        (0..5).any(|v| self.hits & (0b11111 << (v * 5)) == (0b11111 << (v * 5))) ||
-        // Verticals
-        (0..5).any(|v| self.hits & (0b100001000010000100001 << v) == (0b100001000010000100001 << v))
+        let n = self.size;
+        (0..n).any(|r| (0..n).all(|c| self.hits.get(r * n + c)))
+            || (0..n).any(|c| (0..n).all(|r| self.hits.get(r * n + c)))
+    }
+
+    pub fn wins(&self, patterns: &WinPatterns) -> bool {
+        assert_eq!(patterns.size(), self.size, "patterns are for another size");
+        patterns.is_win(&self.hits)
     }
 }
 
 impl fmt::Debug for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f)?;
-        for i in 0..5 {
-            for j in 0..5 {
-                let hit = self.hits & 1 << (i * 5 + j) != 0;
-                if hit {
-                    write!(f, "(")?;
-                } else {
-                    write!(f, " ")?;
-                }
-                write!(f, "{:>3}", self.board[i][j])?;
-                if hit {
-                    write!(f, ")")?;
-                } else {
-                    write!(f, " ")?;
-                }
+        for (offset, v) in self.cells.iter().enumerate() {
+            if self.hits.get(offset) {
+                write!(f, "({:>3})", v)?;
+            } else {
+                write!(f, " {:>3} ", v)?;
+            }
+            if offset % self.size == self.size - 1 {
+                writeln!(f)?;
             }
-            writeln!(f)?;
         }
         Ok(())
     }
 }
 
-pub fn parse(s: &str) -> (Vec<u8>, Vec<Board>) {
-    let mut lines = s.lines();
-    let numbers = lines
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|v| u8::from_str(v).unwrap())
-        .collect::<Vec<_>>();
+pub fn parse(s: &str) -> Result<(Vec<Number>, Vec<Board>), ParseError> {
+    let mut lines = parse::lines(s);
+    let numbers = parse::numbers(&lines.expect()?)?;
     let mut boards = vec![];
 
-    while lines.next().is_some() {
-        let (lines_, board) = parse_board(lines);
-        lines = lines_;
-        boards.push(board);
+    while let Some(l) = lines.next() {
+        if !l.text.trim().is_empty() {
+            return Err(l.error(l.text, ErrorKind::Invalid("expected a blank line")));
+        }
+        boards.push(parse_board(&mut lines)?);
     }
 
-    (numbers, boards)
+    Ok((numbers, boards))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Drawn {
+        draw: usize,
+        number: Number,
+    },
+    Marked {
+        board: usize,
+        draw: usize,
+        cell: usize,
+    },
+    Won {
+        board: usize,
+        draw: usize,
+        score: u64,
+    },
+}
+
+/// When and how a board won. `draw` is the index of the winning number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Finish {
+    pub board: usize,
+    pub draw: usize,
+    pub number: Number,
+    pub score: u64,
+}
+
+fn win_patterns(size: usize, patterns: &[Pattern]) -> WinPatterns {
+    patterns
+        .iter()
+        .fold(WinPatterns::new(size), |p, pattern| p.with(pattern.clone()))
+}
+
+/// Plays a set of boards through a draw. Boards drop out as they win, so
+/// `finishes` ends up listing every board that wins, in order; boards that
+/// win on the same number are listed by index.
+///
+/// Each number is looked up in an index of where it appears, so a draw only
+/// touches the boards which have it.
+pub struct Game {
+    boards: Vec<Board>,
+    patterns: HashMap<usize, WinPatterns>,
+    index: HashMap<Number, Vec<(usize, usize)>>,
+    won: Vec<bool>,
+    draws: usize,
+    finishes: Vec<Finish>,
 }
 
-pub fn part_1(numbers: Vec<u8>, mut boards: Vec<Board>) -> u64 {
-    for number in numbers {
-        for board in boards.iter_mut() {
-            if board.hit(number) && board.is_bingo() {
-                return number as u64 * board.sum_unmarked();
+impl Game {
+    /// Any `Pattern::Custom` masks must fit every board.
+    pub fn new(boards: Vec<Board>, patterns: &[Pattern]) -> Self {
+        let mut by_size = HashMap::new();
+        let mut index: HashMap<Number, Vec<(usize, usize)>> = HashMap::new();
+        for (idx, board) in boards.iter().enumerate() {
+            by_size
+                .entry(board.size())
+                .or_insert_with(|| win_patterns(board.size(), patterns));
+            for (cell, number) in board.cells().iter().enumerate() {
+                let places = index.entry(*number).or_default();
+                // like `Board::hit`, only the first copy of a number counts
+                if places.last().is_none_or(|(b, _)| *b != idx) {
+                    places.push((idx, cell));
+                }
+            }
+        }
+        Game {
+            won: vec![false; boards.len()],
+            boards,
+            patterns: by_size,
+            index,
+            draws: 0,
+            finishes: vec![],
+        }
+    }
+
+    /// Rows and columns win, as in the puzzle.
+    pub fn standard(boards: Vec<Board>) -> Self {
+        Game::new(boards, &[Pattern::Rows, Pattern::Columns])
+    }
+
+    pub fn draw(&mut self, number: Number, on_event: &mut dyn FnMut(&Event)) {
+        let draw = self.draws;
+        self.draws += 1;
+        on_event(&Event::Drawn { draw, number });
+
+        let places = match self.index.get(&number) {
+            Some(places) => places,
+            None => return,
+        };
+        for &(idx, cell) in places {
+            if self.won[idx] {
+                continue;
+            }
+            let board = &mut self.boards[idx];
+            board.mark(cell);
+            on_event(&Event::Marked {
+                board: idx,
+                draw,
+                cell,
+            });
+            if self.patterns[&board.size()].is_win_at(board.hits(), cell) {
+                let score = number as u64 * board.sum_unmarked();
+                self.won[idx] = true;
+                self.finishes.push(Finish {
+                    board: idx,
+                    draw,
+                    number,
+                    score,
+                });
+                on_event(&Event::Won {
+                    board: idx,
+                    draw,
+                    score,
+                });
             }
         }
     }
-    unimplemented!()
+
+    pub fn play(&mut self, numbers: &[Number], on_event: &mut dyn FnMut(&Event)) {
+        for number in numbers {
+            self.draw(*number, on_event);
+        }
+    }
+
+    pub fn boards(&self) -> &[Board] {
+        &self.boards
+    }
+
+    pub fn finishes(&self) -> &[Finish] {
+        &self.finishes
+    }
 }
 
-pub fn part_2(numbers: Vec<u8>, mut boards: Vec<Board>) -> u64 {
-    let mut completion_sequence = vec![0; boards.len()];
-    let mut seq_start = 1;
-    for number in numbers {
+/// The finishing order under the puzzle's rules, found the straightforward
+/// way: every number is checked against every board still playing. `Game`
+/// gives the same answer faster; this is kept to compare against.
+pub fn play_scanning(numbers: &[Number], mut boards: Vec<Board>) -> Vec<Finish> {
+    let mut won = vec![false; boards.len()];
+    let mut finishes = vec![];
+    for (draw, &number) in numbers.iter().enumerate() {
         for (idx, board) in boards.iter_mut().enumerate() {
-            if completion_sequence[idx] == 0 {
-                assert!(!board.is_bingo());
-                if board.hit(number) && board.is_bingo() {
-                    completion_sequence[idx] = seq_start;
-                    seq_start += 1;
+            if !won[idx] && board.hit(number) && board.is_bingo() {
+                won[idx] = true;
+                finishes.push(Finish {
+                    board: idx,
+                    draw,
+                    number,
+                    score: number as u64 * board.sum_unmarked(),
+                });
+            }
+        }
+    }
+    finishes
+}
+
+/// Plays the whole draw under the puzzle's rules.
+pub fn play(numbers: &[Number], boards: Vec<Board>) -> Game {
+    let mut game = Game::standard(boards);
+    game.play(numbers, &mut |_| ());
+    game
+}
+
+pub fn part_1(numbers: Vec<Number>, boards: Vec<Board>) -> u64 {
+    let game = play(&numbers, boards);
+    game.finishes().first().expect("no board wins").score
+}
+
+pub fn part_2(numbers: Vec<Number>, boards: Vec<Board>) -> u64 {
+    let game = play(&numbers, boards);
+    game.finishes().last().expect("no board wins").score
+}
+
+/// A board's chances of winning first and last, when every number below
+/// some limit is drawn in a uniformly random order. As in `Game`, boards which
+/// win on the same number finish in order of index, so a tie goes to the
+/// lowest index for first place and the highest for last.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Odds {
+    pub first: f64,
+    pub last: f64,
+}
+
+/// `exact_odds` gives up when more distinct numbers than this are in play.
+pub const EXACT_LIMIT: usize = 10;
+
+// The numbers which complete each of `board`'s patterns, leaving out any
+// pattern that needs a number which isn't `available`. As in `Game`, repeats
+// on a board never get marked.
+fn winning_draws(
+    board: &Board,
+    patterns: &WinPatterns,
+    available: impl Fn(Number) -> bool,
+) -> Vec<Vec<Number>> {
+    let mut seen = HashSet::new();
+    let marked_by = board
+        .cells()
+        .iter()
+        .map(|n| (available(*n) && seen.insert(*n)).then_some(*n))
+        .collect::<Vec<_>>();
+    patterns
+        .masks()
+        .iter()
+        .filter_map(|mask| {
+            mask.cells()
+                .map(|c| marked_by[c])
+                .collect::<Option<Vec<_>>>()
+        })
+        .collect()
+}
+
+// Only the relative order of the numbers on the boards matters, so each board
+// is boiled down to its ways of winning, as lists of indices into the numbers
+// in play. Draw orders then become permutations of those numbers.
+struct OddsModel {
+    numbers: usize,
+    wins: Vec<Vec<Vec<usize>>>,
+}
+
+impl OddsModel {
+    fn new(boards: &[Board], patterns: &[Pattern], range: Number) -> Self {
+        let mut by_size = HashMap::new();
+        let mut in_play = HashMap::new();
+        let wins = boards
+            .iter()
+            .map(|board| {
+                let patterns = by_size
+                    .entry(board.size())
+                    .or_insert_with(|| win_patterns(board.size(), patterns));
+                winning_draws(board, patterns, |n| n < range)
+                    .into_iter()
+                    .map(|win| {
+                        win.into_iter()
+                            .map(|n| {
+                                let next = in_play.len();
+                                *in_play.entry(n).or_insert(next)
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        OddsModel {
+            numbers: in_play.len(),
+            wins,
+        }
+    }
+
+    // Credits the boards which win first and last when the numbers are drawn
+    // so that number `i` comes out at `position[i]`.
+    fn tally(&self, position: &[usize], odds: &mut [Odds]) {
+        let times = self
+            .wins
+            .iter()
+            .map(|wins| {
+                wins.iter()
+                    .map(|win| win.iter().map(|n| position[*n]).max().unwrap_or(0))
+                    .min()
+            })
+            .collect::<Vec<_>>();
+        // as in `Game`, boards which win on the same number finish in order
+        // of index: `min_by_key` keeps the first of equals, `max_by_key` the
+        // last
+        let finishers = || {
+            times
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, time)| Some((idx, (*time)?)))
+        };
+        if let Some((idx, _)) = finishers().min_by_key(|(_, time)| *time) {
+            odds[idx].first += 1.0;
+        }
+        if let Some((idx, _)) = finishers().max_by_key(|(_, time)| *time) {
+            odds[idx].last += 1.0;
+        }
+    }
+}
+
+/// Estimates the odds by playing `trials` random draws of every number below
+/// `range`, shuffled with a generator seeded by `seed`. Panics if `trials` is
+/// zero.
+pub fn estimate_odds(
+    boards: &[Board],
+    patterns: &[Pattern],
+    range: Number,
+    trials: usize,
+    seed: u64,
+) -> Vec<Odds> {
+    assert!(trials > 0, "no trials to estimate from");
+    let model = OddsModel::new(boards, patterns, range);
+    let mut rng = Rng::new(seed);
+    let mut odds = vec![Odds::default(); boards.len()];
+    let mut position = (0..model.numbers).collect::<Vec<_>>();
+    for _ in 0..trials {
+        rng.shuffle(&mut position);
+        model.tally(&position, &mut odds);
+    }
+    for o in &mut odds {
+        o.first /= trials as f64;
+        o.last /= trials as f64;
+    }
+    odds
+}
+
+/// Works the odds out exactly by trying every order of the numbers in play,
+/// or returns `None` if there are more than `EXACT_LIMIT` of them.
+pub fn exact_odds(boards: &[Board], patterns: &[Pattern], range: Number) -> Option<Vec<Odds>> {
+    let model = OddsModel::new(boards, patterns, range);
+    if model.numbers > EXACT_LIMIT {
+        return None;
+    }
+    let mut odds = vec![Odds::default(); boards.len()];
+    let mut position = (0..model.numbers).collect::<Vec<_>>();
+
+    // Heap's algorithm, iteratively
+    let mut counters = vec![0; model.numbers];
+    let mut orders = 1;
+    model.tally(&position, &mut odds);
+    let mut i = 1;
+    while i < model.numbers {
+        if counters[i] < i {
+            let j = if i % 2 == 0 { 0 } else { counters[i] };
+            position.swap(j, i);
+            model.tally(&position, &mut odds);
+            orders += 1;
+            counters[i] += 1;
+            i = 1;
+        } else {
+            counters[i] = 0;
+            i += 1;
+        }
+    }
+
+    for o in &mut odds {
+        o.first /= orders as f64;
+        o.last /= orders as f64;
+    }
+    Some(odds)
+}
+
+/// What a rigged draw should get the chosen board to do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    /// Win before every other board.
+    First,
+    /// Win after every other board that wins at all.
+    Last,
+    /// Win with the highest score it can.
+    MaxScore,
+}
+
+// Whether every number of `win` has been drawn, counting `extra` too.
+fn drawn(win: &BTreeSet<Number>, drawn: &BTreeSet<Number>, extra: Option<Number>) -> bool {
+    win.iter().all(|n| drawn.contains(n) || Some(*n) == extra)
+}
+
+// Looks for numbers to hold back from the target, such that it can't win
+// until one of them comes out, while every other board can win without them.
+// A board that ties go to may instead need the first held number to be drawn,
+// which comes back alongside. Boards that need more than that can't catch up
+// once `held` grows, so those branches stop early; `skip` holds numbers tried
+// by earlier branches, so no set is looked at twice.
+fn hold_back(
+    ours: &[BTreeSet<Number>],
+    others: &[(bool, &[BTreeSet<Number>])],
+    held: &mut BTreeSet<Number>,
+    skip: &mut BTreeSet<Number>,
+) -> Option<(BTreeSet<Number>, Option<Number>)> {
+    let blocked = |(ties, wins): &(bool, &[BTreeSet<Number>])| {
+        !wins
+            .iter()
+            .any(|w| w.intersection(held).count() <= *ties as usize)
+    };
+    if others.iter().any(blocked) {
+        return None;
+    }
+
+    let win = match ours.iter().find(|w| w.is_disjoint(held)) {
+        Some(win) => win,
+        None => {
+            let mut firsts: Option<BTreeSet<Number>> = None;
+            for (_, wins) in others {
+                if wins.iter().any(|w| w.is_disjoint(held)) {
+                    continue;
                 }
+                let options = wins
+                    .iter()
+                    .filter_map(|w| {
+                        let mut needed = w.intersection(held);
+                        match (needed.next(), needed.next()) {
+                            (Some(n), None) => Some(*n),
+                            _ => None,
+                        }
+                    })
+                    .collect::<BTreeSet<_>>();
+                firsts = Some(match firsts {
+                    Some(firsts) => &firsts & &options,
+                    None => options,
+                });
             }
+            return match firsts {
+                Some(firsts) => firsts.first().map(|n| (held.clone(), Some(*n))),
+                None => Some((held.clone(), None)),
+            };
+        }
+    };
+
+    let mut found = None;
+    let mut tried = vec![];
+    for n in win.difference(skip).copied().collect::<Vec<_>>() {
+        held.insert(n);
+        found = hold_back(ours, others, held, skip);
+        held.remove(&n);
+        if found.is_some() {
+            break;
+        }
+        skip.insert(n);
+        tried.push(n);
+    }
+    for n in tried {
+        skip.remove(&n);
+    }
+    found
+}
+
+/// Orders the numbers in `pool` so that, once they've all been drawn, board
+/// `target` has met `goal`. Ties on the same number go to the lower index, as
+/// in `Game`. Returns `None` if no order does it; the search is exhaustive,
+/// so that's a proof.
+pub fn rig_draw(
+    boards: &[Board],
+    patterns: &[Pattern],
+    pool: &[Number],
+    target: usize,
+    goal: Goal,
+) -> Option<Vec<Number>> {
+    let mut seen = HashSet::new();
+    let pool = pool
+        .iter()
+        .copied()
+        .filter(|n| seen.insert(*n))
+        .collect::<Vec<_>>();
+    let mut by_size = HashMap::new();
+    let wins = boards
+        .iter()
+        .map(|board| {
+            let patterns = by_size
+                .entry(board.size())
+                .or_insert_with(|| win_patterns(board.size(), patterns));
+            winning_draws(board, patterns, |n| seen.contains(&n))
+                .into_iter()
+                .map(|win| win.into_iter().collect::<BTreeSet<_>>())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let ours = &wins[target];
+
+    // every way for the target to win: the numbers drawn before, and the one
+    // that completes a pattern without another having been completed first
+    let finishes = ours
+        .iter()
+        .flat_map(|win| {
+            win.iter().map(move |last| {
+                let mut before = win.clone();
+                before.remove(last);
+                (before, *last)
+            })
+        })
+        .filter(|(before, _)| !ours.iter().any(|w| drawn(w, before, None)))
+        .collect::<Vec<_>>();
+
+    let lead = match goal {
+        Goal::First => {
+            let (before, last) = finishes.into_iter().find(|(before, last)| {
+                wins.iter().enumerate().all(|(idx, theirs)| {
+                    let extra = if idx < target { Some(*last) } else { None };
+                    idx == target || !theirs.iter().any(|w| drawn(w, before, extra))
+                })
+            })?;
+            before.into_iter().chain([last]).collect::<Vec<_>>()
+        }
+        Goal::MaxScore => {
+            let score = |(before, last): &(BTreeSet<Number>, Number)| {
+                let mut board = boards[target].clone();
+                for n in before.iter().chain([last]) {
+                    board.hit(*n);
+                }
+                *last as u64 * board.sum_unmarked()
+            };
+            let best = finishes.iter().map(score).max()?;
+            let (before, last) = finishes.into_iter().find(|f| score(f) == best)?;
+            before.into_iter().chain([last]).collect()
         }
-        if seq_start == boards.len() + 1 {
-            return number as u64
-                * boards[(0..completion_sequence.len())
-                    .find(|idx| completion_sequence[*idx] == seq_start - 1)
-                    .unwrap()]
-                .sum_unmarked();
+        Goal::Last => {
+            if ours.is_empty() {
+                return None;
+            }
+            let others = wins
+                .iter()
+                .enumerate()
+                .filter(|(idx, theirs)| *idx != target && !theirs.is_empty())
+                .map(|(idx, theirs)| (idx < target, theirs.as_slice()))
+                .collect::<Vec<_>>();
+            let (held, first) =
+                hold_back(ours, &others, &mut BTreeSet::new(), &mut BTreeSet::new())?;
+            // everything else comes out before the held numbers
+            let mut lead = pool
+                .iter()
+                .copied()
+                .filter(|n| !held.contains(n))
+                .collect::<Vec<_>>();
+            lead.extend(first);
+            lead
         }
+    };
+
+    let placed = lead.iter().copied().collect::<HashSet<_>>();
+    let rest = pool.into_iter().filter(|n| !placed.contains(n));
+    Some(lead.iter().copied().chain(rest).collect())
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: usize = 4;
+
+    type Input = (Vec<Number>, Vec<Board>);
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        part_1(input.0.clone(), input.1.clone())
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(part_2(input.0.clone(), input.1.clone()))
     }
-    unimplemented!()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse, part_1, part_2};
+    use super::{
+        estimate_odds, exact_odds, parse, part_1, part_2, play, play_scanning, rig_draw, Board,
+        Event, Finish, Game, Goal, Mask, Odds, Pattern, WinPatterns,
+    };
+    use crate::generate::Rng;
 
     const EXAMPLE_INPUT: &str = r#"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
 
@@ -260,27 +953,271 @@ mod tests {
 22 11 13  6  5
  2  0 12  3  7"#;
 
+    fn finishes(boards: &[Board], numbers: &[u32]) -> Vec<Finish> {
+        let mut game = Game::standard(boards.to_vec());
+        game.play(numbers, &mut |_| ());
+        game.finishes().to_vec()
+    }
+
+    // Every order of `pool`.
+    fn orders(pool: &[u32]) -> Vec<Vec<u32>> {
+        let mut orders = vec![vec![]];
+        for n in pool {
+            orders = orders
+                .into_iter()
+                .flat_map(|order: Vec<u32>| {
+                    (0..=order.len()).map(move |i| {
+                        let mut order = order.clone();
+                        order.insert(i, *n);
+                        order
+                    })
+                })
+                .collect();
+        }
+        orders
+    }
+
     #[test]
     fn test_day_4_example_part_1() {
-        let (numbers, boards) = parse(EXAMPLE_INPUT);
+        let (numbers, boards) = parse(EXAMPLE_INPUT).unwrap();
         assert_eq!(part_1(numbers, boards), 4512);
     }
 
     #[test]
     fn test_day_4_part_1() {
-        let (numbers, boards) = parse(include_str!("input/day_4.txt"));
+        let (numbers, boards) = parse(include_str!("input/day_4.txt")).unwrap();
         assert_eq!(part_1(numbers, boards), 23177);
     }
 
     #[test]
     fn test_day_4_example_part_2() {
-        let (numbers, boards) = parse(EXAMPLE_INPUT);
+        let (numbers, boards) = parse(EXAMPLE_INPUT).unwrap();
         assert_eq!(part_2(numbers, boards), 1924);
     }
 
     #[test]
     fn test_day_4_part_2() {
-        let (numbers, boards) = parse(include_str!("input/day_4.txt"));
+        let (numbers, boards) = parse(include_str!("input/day_4.txt")).unwrap();
         assert_eq!(part_2(numbers, boards), 6804);
     }
+
+    #[test]
+    fn test_day_4_parse_error() {
+        let e = parse(&EXAMPLE_INPUT.replace("25 23", "25 2e")).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (11, 13, "2e"));
+        let e = parse(&EXAMPLE_INPUT.replace(" 2  0 12  3  7", "")).unwrap_err();
+        assert_eq!((e.line, e.column), (19, 1));
+    }
+
+    #[test]
+    fn test_day_4_win_patterns() {
+        let (numbers, boards) = parse("1000,5,9,300,7\n\n1000 2 300\n4 5 6\n7 8 9").unwrap();
+        assert_eq!(numbers[0], 1000);
+        let mut board = boards[0].clone();
+        assert_eq!(board.size(), 3);
+
+        let diagonals = WinPatterns::new(3).with(Pattern::Diagonals);
+        let corners = WinPatterns::new(3).with(Pattern::FourCorners);
+        let blackout = WinPatterns::new(3).with(Pattern::Blackout);
+        let custom =
+            WinPatterns::new(3).with(Pattern::Custom(Mask::from_cells(3, &[(1, 1), (2, 0)])));
+
+        for v in &numbers[..3] {
+            board.hit(*v);
+        }
+        assert!(board.wins(&diagonals));
+        assert!(!board.is_bingo() && !board.wins(&corners));
+        assert_eq!(board.sum_unmarked(), 2 + 300 + 4 + 6 + 7 + 8);
+        board.hit(7);
+        assert!(board.wins(&custom) && !board.wins(&corners));
+        assert!(!board.wins(&WinPatterns::standard(3)));
+        assert!(!board.wins(&blackout));
+
+        let mut full = Board::new(3, (1..=9).collect());
+        (1..=9).for_each(|v| assert!(full.hit(v)));
+        assert!(full.wins(&blackout) && full.is_bingo());
+        assert_eq!(full.sum_unmarked(), 0);
+
+        let e = parse("1\n\n1 2 3\n4 5 6 7\n7 8 9").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (4, 7, "7"));
+    }
+
+    #[test]
+    #[should_panic(expected = "mask is for another board size")]
+    fn test_day_4_custom_mask_size() {
+        // 25 and 36 cells both fit in one word, so only the size tells them apart
+        let mask = Mask::from_cells(5, &[(0, 0), (4, 4)]);
+        assert_eq!(mask.board_cells(), 25);
+        WinPatterns::new(6).with(Pattern::Custom(mask));
+    }
+
+    #[test]
+    #[should_panic(expected = "mask is empty")]
+    fn test_day_4_custom_mask_empty() {
+        WinPatterns::new(5).with(Pattern::Custom(Mask::from_cells(5, &[])));
+    }
+
+    #[test]
+    fn test_day_4_game() {
+        let (numbers, boards) = parse(EXAMPLE_INPUT).unwrap();
+        let mut events = vec![];
+        let mut game = Game::standard(boards.clone());
+        game.play(&numbers, &mut |e| events.push(e.clone()));
+
+        let order = game
+            .finishes()
+            .iter()
+            .map(|f| (f.board, f.draw, f.score))
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec![(2, 11, 4512), (0, 13, 2192), (1, 14, 1924)]);
+        assert_eq!(events[0], Event::Drawn { draw: 0, number: 7 });
+        assert_eq!(
+            events[1],
+            Event::Marked {
+                board: 0,
+                draw: 0,
+                cell: 14
+            }
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, Event::Drawn { .. }))
+                .count(),
+            numbers.len()
+        );
+        let won = events.iter().position(|e| matches!(e, Event::Won { .. }));
+        assert_eq!(
+            events[won.unwrap()],
+            Event::Won {
+                board: 2,
+                draw: 11,
+                score: 4512
+            }
+        );
+
+        // with diagonals too, the third board's anti-diagonal (4 9 23 11 2)
+        // wins it the game four numbers sooner
+        let mut game = Game::new(
+            boards,
+            &[Pattern::Rows, Pattern::Columns, Pattern::Diagonals],
+        );
+        game.play(&numbers, &mut |_| ());
+        let first = game.finishes()[0];
+        assert_eq!((first.board, first.draw, first.score), (2, 7, 494));
+        assert_eq!(game.finishes().len(), 3);
+    }
+
+    #[test]
+    fn test_day_4_index_matches_scan() {
+        let (numbers, boards) = parse(include_str!("input/day_4.txt")).unwrap();
+        assert_eq!(
+            play(&numbers, boards.clone()).finishes(),
+            play_scanning(&numbers, boards)
+        );
+
+        // bigger boards, a wider range of numbers, and some repeats
+        let mut rng = Rng::new(4);
+        let boards = (0..200)
+            .map(|_| Board::new(7, (0..49).map(|_| rng.below(400) as u32).collect()))
+            .collect::<Vec<_>>();
+        let mut numbers = (0..400).collect::<Vec<_>>();
+        rng.shuffle(&mut numbers);
+        let finishes = play_scanning(&numbers, boards.clone());
+        assert_eq!(finishes.len(), 200);
+        assert_eq!(play(&numbers, boards).finishes(), finishes);
+    }
+
+    #[test]
+    fn test_day_4_odds() {
+        let standard = [Pattern::Rows, Pattern::Columns];
+        // the second board is the first with every number n swapped for
+        // 5 - n, and the third needs a number that's never drawn
+        let mut boards = vec![
+            Board::new(2, vec![0, 1, 2, 3]),
+            Board::new(2, vec![5, 4, 3, 2]),
+            Board::new(1, vec![6]),
+        ];
+        // the same as playing every order through the engine
+        let mut played = vec![Odds::default(); boards.len()];
+        for order in orders(&[0, 1, 2, 3, 4, 5]) {
+            let finishes = finishes(&boards, &order);
+            played[finishes[0].board].first += 1.0 / 720.0;
+            played[finishes.last().unwrap().board].last += 1.0 / 720.0;
+        }
+        let exact = exact_odds(&boards, &standard, 6).unwrap();
+        for (x, p) in exact.iter().zip(&played) {
+            assert!((x.first - p.first).abs() < 1e-12, "{:?} vs {:?}", x, p);
+            assert!((x.last - p.last).abs() < 1e-12, "{:?} vs {:?}", x, p);
+        }
+        // the mirror images only differ when they tie, and ties go to the
+        // lower index first and the higher one last
+        assert!((exact[0].first + exact[1].first - 1.0).abs() < 1e-12);
+        assert!(exact[0].first > exact[1].first && exact[1].last > exact[0].last);
+        assert_eq!((exact[2].first, exact[2].last), (0.0, 0.0));
+
+        // a copy of the first board always ties with it, so it's never first
+        // and the original is never last
+        boards.push(boards[0].clone());
+        let exact = exact_odds(&boards, &standard, 6).unwrap();
+        assert_eq!((exact[3].first, exact[0].last), (0.0, 0.0));
+        let total = exact.iter().map(|o| o.first).sum::<f64>();
+        assert!((total - 1.0).abs() < 1e-12);
+
+        let estimate = estimate_odds(&boards, &standard, 6, 4000, 23);
+        for (e, x) in estimate.iter().zip(&exact) {
+            assert!((e.first - x.first).abs() < 0.03, "{:?} vs {:?}", e, x);
+            assert!((e.last - x.last).abs() < 0.03, "{:?} vs {:?}", e, x);
+        }
+
+        let (_, boards) = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(exact_odds(&boards, &standard, 27), None);
+        let odds = estimate_odds(&boards, &standard, 27, 200, 1);
+        assert!((odds.iter().map(|o| o.last).sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "no trials")]
+    fn test_day_4_odds_without_trials() {
+        estimate_odds(&[Board::new(1, vec![0])], &[Pattern::Rows], 1, 0, 1);
+    }
+
+    #[test]
+    fn test_day_4_rig_draw() {
+        let standard = [Pattern::Rows, Pattern::Columns];
+
+        let (numbers, boards) = parse(EXAMPLE_INPUT).unwrap();
+        for target in 0..boards.len() {
+            let first = rig_draw(&boards, &standard, &numbers, target, Goal::First).unwrap();
+            let last = rig_draw(&boards, &standard, &numbers, target, Goal::Last).unwrap();
+            assert_eq!(finishes(&boards, &first)[0].board, target);
+            assert_eq!(finishes(&boards, &last).last().unwrap().board, target);
+            assert_eq!(finishes(&boards, &last).len(), boards.len());
+        }
+
+        // a copy of a board ties with it, and ties go to the lower index
+        let boards = vec![
+            Board::new(2, vec![0, 1, 2, 3]),
+            Board::new(2, vec![0, 1, 2, 3]),
+            Board::new(2, vec![5, 4, 3, 2]),
+        ];
+        let pool = (0..6).collect::<Vec<_>>();
+        assert_eq!(rig_draw(&boards, &standard, &pool, 1, Goal::First), None);
+        assert_eq!(rig_draw(&boards, &standard, &pool, 0, Goal::Last), None);
+        let last = rig_draw(&boards, &standard, &pool, 1, Goal::Last).unwrap();
+        assert_eq!(finishes(&boards, &last).last().unwrap().board, 1);
+
+        // no draw of the pool beats the best score
+        let best = rig_draw(&boards, &standard, &pool, 2, Goal::MaxScore).unwrap();
+        let score = |numbers: &[u32]| {
+            finishes(&boards, numbers)
+                .iter()
+                .find(|f| f.board == 2)
+                .map_or(0, |f| f.score)
+        };
+        let orders = orders(&pool);
+        assert_eq!(orders.len(), 720);
+        assert_eq!(score(&best), orders.iter().map(|o| score(o)).max().unwrap());
+        assert_eq!(score(&best), 4 * (5 + 3));
+    }
 }
@@ -88,27 +88,40 @@
 
 // Consider all of the lines. At how many points do at least two lines overlap?
 
-use std::boxed::Box;
 use std::collections::HashMap;
 
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
+
+pub type Point = (i64, i64);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Line {
-    start: (usize, usize),
-    end: (usize, usize),
+    start: Point,
+    end: Point,
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
 }
 
 impl Line {
-    pub fn new(s: &str) -> Line {
-        let (start, end) = s.split_once("->").unwrap();
-        let (start_x, start_y) = start.split_once(",").unwrap();
-        let (end_x, end_y) = end.split_once(",").unwrap();
-        Line {
-            start: (
-                start_x.trim().parse().unwrap(),
-                start_y.trim().parse().unwrap(),
-            ),
-            end: (end_x.trim().parse().unwrap(), end_y.trim().parse().unwrap()),
-        }
+    pub fn new(start: Point, end: Point) -> Line {
+        Line { start, end }
+    }
+
+    pub fn parse(l: &parse::Line<'_>) -> Result<Line, ParseError> {
+        let (start, end) = l.split_once(l.text, "->")?;
+        let (start_x, start_y) = l.split_once(start, ",")?;
+        let (end_x, end_y) = l.split_once(end, ",")?;
+        Ok(Line {
+            start: (l.number(start_x)?, l.number(start_y)?),
+            end: (l.number(end_x)?, l.number(end_y)?),
+        })
     }
 
     pub fn is_horizontal(&self) -> bool {
@@ -120,77 +133,56 @@ impl Line {
     }
 
     pub fn is_diagonal(&self) -> bool {
-        (self.start.0.max(self.end.0) - self.start.0.min(self.end.0))
-            == (self.start.1.max(self.end.1) - self.start.1.min(self.end.1))
-    }
-
-    pub fn points_iter(&self) -> Box<dyn Iterator<Item = (usize, usize)> + '_> {
-        if self.is_horizontal() {
-            if self.start.0 <= self.end.0 {
-                Box::new((self.start.0..=self.end.0).map(move |x| (x, self.start.1)))
-            } else {
-                Box::new((self.end.0..=self.start.0).map(move |x| (x, self.start.1)))
-            }
-        } else if self.is_vertical() {
-            if self.start.1 <= self.end.1 {
-                Box::new((self.start.1..=self.end.1).map(move |y| (self.start.0, y)))
-            } else {
-                Box::new((self.end.1..=self.start.1).map(move |y| (self.start.0, y)))
-            }
-        } else if self.is_diagonal() {
-            if self.start.0 < self.end.0 {
-                if self.start.1 < self.end.1 {
-                    // diagonal up-right at 45 degrees
-                    Box::new(
-                        (0..=self.end.0 - self.start.0)
-                            .map(move |offset| (self.start.0 + offset, self.start.1 + offset)),
-                    )
-                } else {
-                    // diagonal down-right at 45 degrees
-                    Box::new(
-                        (0..=self.end.0 - self.start.0)
-                            .map(move |offset| (self.start.0 + offset, self.start.1 - offset)),
-                    )
-                }
-            } else if self.start.1 < self.end.1 {
-                // diagonal up-left at 45 degrees
-                Box::new(
-                    (0..=self.start.0 - self.end.0)
-                        .map(move |offset| (self.start.0 - offset, self.start.1 + offset)),
-                )
-            } else {
-                // diagonal down-left at 45 degrees
-                Box::new(
-                    (0..=self.start.0 - self.end.0)
-                        .map(move |offset| (self.start.0 - offset, self.start.1 - offset)),
-                )
-            }
+        (self.end.0 - self.start.0).abs() == (self.end.1 - self.start.1).abs()
+    }
+
+    /// The points with integer coordinates that the line passes through,
+    /// from start to end. Only horizontal, vertical and 45 degree lines cover
+    /// every step between their ends; others skip the points they pass
+    /// between.
+    pub fn points_iter(&self) -> impl Iterator<Item = Point> {
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        // a line from a point to itself still covers that point
+        let steps = gcd(dx, dy);
+        let (step_x, step_y) = if steps == 0 {
+            (0, 0)
         } else {
-            unimplemented!()
-        }
+            (dx / steps, dy / steps)
+        };
+        let start = self.start;
+        (0..=steps).map(move |i| (start.0 + i * step_x, start.1 + i * step_y))
     }
 }
 
-pub fn part_1(s: &str) -> usize {
-    let lines = s.lines().map(Line::new);
-    let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+/// Which lines to count, by slope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slopes {
+    /// Horizontal and vertical lines, as in part 1.
+    Straight,
+    /// Those and 45 degree diagonals, as in part 2.
+    Diagonal,
+    /// Lines at any slope.
+    Any,
+}
 
-    for line in lines {
-        if line.is_horizontal() || line.is_vertical() {
-            for point in line.points_iter() {
-                *counts.entry(point).or_default() += 1;
-            }
+impl Slopes {
+    pub fn admits(self, line: &Line) -> bool {
+        match self {
+            Slopes::Straight => line.is_horizontal() || line.is_vertical(),
+            Slopes::Diagonal => line.is_horizontal() || line.is_vertical() || line.is_diagonal(),
+            Slopes::Any => true,
         }
     }
+}
 
-    counts.iter().filter(|(_, ct)| **ct >= 2).count()
+pub fn parse(s: &str) -> Result<Vec<Line>, ParseError> {
+    parse::lines(s).map(|l| Line::parse(&l)).collect()
 }
 
-pub fn part_2(s: &str) -> usize {
-    let lines = s.lines().map(Line::new);
-    let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+pub fn count_overlaps(lines: &[Line], slopes: Slopes) -> usize {
+    let mut counts: HashMap<Point, usize> = HashMap::new();
 
-    for line in lines {
+    for line in lines.iter().filter(|line| slopes.admits(line)) {
         for point in line.points_iter() {
             *counts.entry(point).or_default() += 1;
         }
@@ -199,9 +191,39 @@ pub fn part_2(s: &str) -> usize {
     counts.iter().filter(|(_, ct)| **ct >= 2).count()
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(count_overlaps(&parse(s)?, Slopes::Straight))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(count_overlaps(&parse(s)?, Slopes::Diagonal))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: usize = 5;
+
+    type Input = Vec<Line>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        count_overlaps(input, Slopes::Straight)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(count_overlaps(input, Slopes::Diagonal))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{count_overlaps, parse, part_1, part_2, Line, Slopes};
 
     const EXAMPLE: &str = r#"0,9 -> 5,9
 8,0 -> 0,8
@@ -216,21 +238,58 @@ mod tests {
 
     #[test]
     fn test_day_5_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 5);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 5);
     }
 
     #[test]
     fn test_day_5_part_1() {
-        assert_eq!(part_1(include_str!("input/day_5.txt")), 4826);
+        assert_eq!(part_1(include_str!("input/day_5.txt")).unwrap(), 4826);
     }
 
     #[test]
     fn test_day_5_example_part_2() {
-        assert_eq!(part_2(EXAMPLE), 12);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 12);
     }
 
     #[test]
     fn test_day_5_part_2() {
-        assert_eq!(part_2(include_str!("input/day_5.txt")), 16793);
+        assert_eq!(part_2(include_str!("input/day_5.txt")).unwrap(), 16793);
+    }
+
+    #[test]
+    fn test_day_5_parse_error() {
+        let e = parse("0,9 -> 5,9\n8,0 -> 0;8").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 7, " 0;8"));
+        let e = parse("0,9 -> 5,-9x").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 10, "-9x"));
+    }
+
+    #[test]
+    fn test_day_5_any_slope() {
+        let points = |s: &str| {
+            let lines = parse(s).unwrap();
+            lines[0].points_iter().collect::<Vec<_>>()
+        };
+        assert_eq!(points("0,0 -> 6,4"), vec![(0, 0), (3, 2), (6, 4)]);
+        assert_eq!(
+            points("-2,3 -> 4,-6"),
+            vec![(-2, 3), (0, 0), (2, -3), (4, -6)]
+        );
+        assert_eq!(points("0,0 -> 3,-1"), vec![(0, 0), (3, -1)]);
+        assert_eq!(points("1,-1 -> 1,-1"), vec![(1, -1)]);
+        assert_eq!(points("3,3 -> 1,1"), vec![(3, 3), (2, 2), (1, 1)]);
+
+        // the vertical line crosses the others at 3,2 and 3,0, but it's the
+        // only one the puzzle's filters keep
+        let lines = vec![
+            Line::new((0, 0), (6, 4)),
+            Line::new((3, -1), (3, 5)),
+            Line::new((-1, -2), (5, 1)),
+        ];
+        assert_eq!(count_overlaps(&lines, Slopes::Straight), 0);
+        assert_eq!(count_overlaps(&lines, Slopes::Diagonal), 0);
+        assert_eq!(count_overlaps(&lines, Slopes::Any), 2);
+        assert!(Slopes::Diagonal.admits(&Line::new((1, -1), (-1, 1))));
+        assert!(!Slopes::Diagonal.admits(&lines[2]));
     }
 }
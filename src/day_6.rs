@@ -84,13 +84,17 @@
 // How many lanternfish would there be after 256 days?
 use std::collections::HashMap;
 
-pub fn part_1(s: &str) -> usize {
-    let mut fish = s
-        .split(',')
-        .map(|s| s.parse::<u8>().unwrap())
-        .collect::<Vec<_>>();
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
-    for _ in 0..80 {
+pub fn parse(s: &str) -> Result<Vec<u8>, ParseError> {
+    parse::number_list(s)
+}
+
+fn simulate(fish: &[u8], days: usize) -> usize {
+    let mut fish = fish.to_vec();
+
+    for _ in 0..days {
         let mut num_new_fish = 0;
         for f in fish.iter_mut() {
             if *f == 0 {
@@ -106,18 +110,13 @@ pub fn part_1(s: &str) -> usize {
     fish.len()
 }
 
-pub fn part_2(s: &str) -> usize {
-    let fish = s
-        .split(',')
-        .map(|s| s.parse::<u8>().unwrap())
-        .collect::<Vec<_>>();
-
+fn simulate_counts(fish: &[u8], days: usize) -> usize {
     let mut fish_reduced: HashMap<u8, usize> = HashMap::new();
     for f in fish {
-        *fish_reduced.entry(f).or_default() += 1;
+        *fish_reduced.entry(*f).or_default() += 1;
     }
 
-    for _ in 0..256 {
+    for _ in 0..days {
         let at_zero = fish_reduced.remove(&0);
         for j in 1..=8 {
             if let Some(v) = fish_reduced.remove(&j) {
@@ -134,27 +133,66 @@ pub fn part_2(s: &str) -> usize {
     fish_reduced.values().sum()
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(simulate(&parse(s)?, 80))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(simulate_counts(&parse(s)?, 256))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: usize = 6;
+
+    type Input = Vec<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        simulate(input, 80)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(simulate_counts(input, 256))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
 
     #[test]
     fn test_day_6_example_part_1() {
-        assert_eq!(part_1("3,4,3,1,2"), 5934);
+        assert_eq!(part_1("3,4,3,1,2").unwrap(), 5934);
     }
 
     #[test]
     fn test_day_6_part_1() {
-        assert_eq!(part_1(include_str!("input/day_6.txt")), 352195);
+        assert_eq!(part_1(include_str!("input/day_6.txt")).unwrap(), 352195);
     }
 
     #[test]
     fn test_day_6_example_part_2() {
-        assert_eq!(part_2("3,4,3,1,2"), 26984457539);
+        assert_eq!(part_2("3,4,3,1,2").unwrap(), 26984457539);
     }
 
     #[test]
     fn test_day_6_part_2() {
-        assert_eq!(part_2(include_str!("input/day_6.txt")), 1600306001288);
+        assert_eq!(
+            part_2(include_str!("input/day_6.txt")).unwrap(),
+            1600306001288
+        );
+    }
+
+    #[test]
+    fn test_day_6_parse_error() {
+        let e = parse("3,4,3,x,2").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 7, "x"));
     }
 }
@@ -79,18 +79,18 @@
 // fuel possible so they can make you an escape route! How much fuel must they
 // spend to align to that position?
 
-pub fn part_1(s: &str) -> u64 {
-    let mut positions = s
-        .split(',')
-        .map(|u| u.parse::<u64>().unwrap())
-        .collect::<Vec<_>>();
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
+
+pub fn parse(s: &str) -> Result<Vec<u64>, ParseError> {
+    parse::number_list(s)
+}
+
+fn fuel_to_median(positions: &[u64]) -> u64 {
+    let mut positions = positions.to_vec();
     positions.sort_unstable();
-    let left = positions.len() / 2;
-    let mid = if positions.len() % 2 == 0 {
-        positions[left]
-    } else {
-        (positions[left] + positions[left + 1]) / 2
-    };
+    // with an even count, anything between the middle two is just as good
+    let mid = positions[positions.len() / 2];
 
     let dist = positions
         .iter()
@@ -100,11 +100,8 @@ pub fn part_1(s: &str) -> u64 {
     dist
 }
 
-pub fn part_2(s: &str) -> usize {
-    let positions = s
-        .split(',')
-        .map(|u| u.parse::<usize>().unwrap())
-        .collect::<Vec<_>>();
+fn triangular_fuel(positions: &[u64]) -> usize {
+    let positions = positions.iter().map(|p| *p as usize).collect::<Vec<_>>();
 
     let min = *positions.iter().min().unwrap();
     let max = *positions.iter().max().unwrap();
@@ -114,7 +111,7 @@ pub fn part_2(s: &str) -> usize {
         costs.push(i + costs[i - 1]);
     }
 
-    let mut best = std::usize::MAX;
+    let mut best = usize::MAX;
     for pos in min..max {
         let mut curr = 0;
         for &num in &positions {
@@ -127,29 +124,71 @@ pub fn part_2(s: &str) -> usize {
     best
 }
 
+pub fn part_1(s: &str) -> Result<u64, ParseError> {
+    Ok(fuel_to_median(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(triangular_fuel(&parse(s)?))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: usize = 7;
+
+    type Input = Vec<u64>;
+    type Answer1 = u64;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        fuel_to_median(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(triangular_fuel(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
 
     #[test]
     fn test_day_7_example_part_1() {
         let input = "16,1,2,0,4,2,7,1,2,14";
-        assert_eq!(part_1(input), 37);
+        assert_eq!(part_1(input).unwrap(), 37);
     }
 
     #[test]
     fn test_day_7_part_1() {
-        assert_eq!(part_1(include_str!("input/day_7.txt")), 352997);
+        assert_eq!(part_1(include_str!("input/day_7.txt")).unwrap(), 352997);
+    }
+
+    #[test]
+    fn test_day_7_odd_count_part_1() {
+        assert_eq!(part_1("0,0,10").unwrap(), 10);
+        assert_eq!(part_1("5").unwrap(), 0);
     }
 
     #[test]
     fn test_day_7_example_part_2() {
         let input = "16,1,2,0,4,2,7,1,2,14";
-        assert_eq!(part_2(input), 168);
+        assert_eq!(part_2(input).unwrap(), 168);
     }
 
     #[test]
     fn test_day_7_part_2() {
-        assert_eq!(part_2(include_str!("input/day_7.txt")), 101571302);
+        assert_eq!(part_2(include_str!("input/day_7.txt")).unwrap(), 101571302);
+    }
+
+    #[test]
+    fn test_day_7_parse_error() {
+        let e = parse("16,1,2,-4").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 8, "-4"));
     }
 }
@@ -167,15 +167,53 @@
 
 use std::collections::HashMap;
 
-pub fn part_1(s: &str) -> usize {
-    let mut freq: HashMap<usize, usize> = HashMap::new();
-    for line in s.lines() {
-        let mut found_delim = false;
-        for segment in line.split_whitespace() {
-            if found_delim {
-                *freq.entry(segment.len()).or_default() += 1;
+use crate::parse::{self, ErrorKind, ParseError};
+use crate::solution::Solution;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    patterns: Vec<String>,
+    outputs: Vec<String>,
+}
+
+pub fn parse(s: &str) -> Result<Vec<Entry>, ParseError> {
+    use std::iter::FromIterator;
+
+    let mut entries = vec![];
+    for line in parse::lines(s) {
+        let (patterns, outputs) = line.split_once(line.text, "|")?;
+
+        // Pre-normalize the segments
+        let normalize = |group: &str, count: usize| {
+            let mut segments = vec![];
+            for segment in group.split_whitespace() {
+                if let Some(c) = segment.chars().find(|c| !('a'..='g').contains(c)) {
+                    let at = &segment[segment.find(c).unwrap()..][..c.len_utf8()];
+                    return Err(line.error(at, ErrorKind::UnexpectedChar));
+                }
+                let mut chars: Vec<_> = segment.chars().collect();
+                chars.sort_unstable();
+                segments.push(String::from_iter(chars));
             }
-            found_delim |= segment == "|";
+            if segments.len() != count {
+                return Err(line.error(group, ErrorKind::Invalid("wrong number of signals")));
+            }
+            Ok(segments)
+        };
+
+        entries.push(Entry {
+            patterns: normalize(patterns, 10)?,
+            outputs: normalize(outputs, 4)?,
+        });
+    }
+    Ok(entries)
+}
+
+fn count_unique_outputs(entries: &[Entry]) -> usize {
+    let mut freq: HashMap<usize, usize> = HashMap::new();
+    for entry in entries {
+        for segment in &entry.outputs {
+            *freq.entry(segment.len()).or_default() += 1;
         }
     }
 
@@ -189,27 +227,12 @@ fn shared_chars(s1: &str, s2: &str) -> usize {
     s1.chars().filter(|c| s2.contains(*c)).count()
 }
 
-pub fn part_2(s: &str) -> usize {
-    use std::iter::FromIterator;
-
+fn sum_outputs(entries: &[Entry]) -> usize {
     let mut total_sum = 0;
 
-    for line in s.lines() {
+    for entry in entries {
         let mut decoder: HashMap<String, usize> = HashMap::new();
-        // Pre-normalize the segments
-        let signals = line
-            .split_whitespace()
-            .map(|s| {
-                let mut chars: Vec<_> = s.chars().collect();
-                chars.sort_unstable();
-                String::from_iter(chars)
-            })
-            .collect::<Vec<_>>();
-        let mut found_delim = false;
-        for segment in &signals {
-            if segment == "|" {
-                break;
-            }
+        for segment in &entry.patterns {
             // Initialize the mappings
             if segment.len() == 2 {
                 decoder.insert(segment.to_owned(), 1);
@@ -225,84 +248,113 @@ pub fn part_2(s: &str) -> usize {
             }
         }
 
-        let mut s = 0;
-        for segment in &signals {
-            if segment == "|" {
-                found_delim = true;
-            } else if found_delim {
-                // decoder
-                s = decoder.get(segment).unwrap() + s * 10;
-            } else {
-                let one = decoder
-                    .iter()
-                    .filter(|(_, v)| **v == 1)
-                    .map(|(k, _)| k)
-                    .next()
-                    .unwrap();
-                let four = decoder
-                    .iter()
-                    .filter(|(_, v)| **v == 4)
-                    .map(|(k, _)| k)
-                    .next()
-                    .unwrap();
-                // Find mappings
-                if decoder.contains_key(segment) {
-                    // continue
-                } else if segment.len() == 5 {
-                    // it's either 2, 3, or 5.
-                    // 2 and 5 share exactly one segment with 1, while 3 shares 2 segments
-                    match shared_chars(segment, one) {
-                        1 => {
-                            // 2 shares 2 segments with 4, 5 shares 3
-                            match shared_chars(segment, four) {
-                                2 => {
-                                    decoder.insert(segment.to_owned(), 2);
-                                }
-                                3 => {
-                                    decoder.insert(segment.to_owned(), 5);
-                                }
-                                x => unreachable!("{:?} unexpected {:?} {:?}", x, segment, four),
+        for segment in &entry.patterns {
+            let one = decoder
+                .iter()
+                .filter(|(_, v)| **v == 1)
+                .map(|(k, _)| k)
+                .next()
+                .unwrap();
+            let four = decoder
+                .iter()
+                .filter(|(_, v)| **v == 4)
+                .map(|(k, _)| k)
+                .next()
+                .unwrap();
+            // Find mappings
+            if decoder.contains_key(segment) {
+                // continue
+            } else if segment.len() == 5 {
+                // it's either 2, 3, or 5.
+                // 2 and 5 share exactly one segment with 1, while 3 shares 2 segments
+                match shared_chars(segment, one) {
+                    1 => {
+                        // 2 shares 2 segments with 4, 5 shares 3
+                        match shared_chars(segment, four) {
+                            2 => {
+                                decoder.insert(segment.to_owned(), 2);
                             }
+                            3 => {
+                                decoder.insert(segment.to_owned(), 5);
+                            }
+                            x => unreachable!("{:?} unexpected {:?} {:?}", x, segment, four),
                         }
-                        2 => {
-                            decoder.insert(segment.to_owned(), 3);
-                        }
-                        x => unreachable!("{:?} unexpected {:?} {:?}", x, segment, one),
                     }
-                } else if segment.len() == 6 {
-                    // it's either 0, 6, or 9.
-                    // 0 and 9 share exactly two segments with 1, while 6 shares 1 segment
-                    match shared_chars(segment, one) {
-                        1 => {
-                            decoder.insert(segment.to_owned(), 6);
-                        }
-                        2 => {
-                            // 0 shares 3 segments with 4, 9 shares 4
-                            match shared_chars(segment, four) {
-                                3 => {
-                                    decoder.insert(segment.to_owned(), 0);
-                                }
-                                4 => {
-                                    decoder.insert(segment.to_owned(), 9);
-                                }
-                                x => unreachable!("{:?} unexpected {:?} {:?}", x, segment, four),
+                    2 => {
+                        decoder.insert(segment.to_owned(), 3);
+                    }
+                    x => unreachable!("{:?} unexpected {:?} {:?}", x, segment, one),
+                }
+            } else if segment.len() == 6 {
+                // it's either 0, 6, or 9.
+                // 0 and 9 share exactly two segments with 1, while 6 shares 1 segment
+                match shared_chars(segment, one) {
+                    1 => {
+                        decoder.insert(segment.to_owned(), 6);
+                    }
+                    2 => {
+                        // 0 shares 3 segments with 4, 9 shares 4
+                        match shared_chars(segment, four) {
+                            3 => {
+                                decoder.insert(segment.to_owned(), 0);
+                            }
+                            4 => {
+                                decoder.insert(segment.to_owned(), 9);
                             }
+                            x => unreachable!("{:?} unexpected {:?} {:?}", x, segment, four),
                         }
-                        x => unreachable!("{:?} unexpected {:?} {:?}", x, segment, one),
                     }
-                } else {
-                    unreachable!("shouldn't get here {:?} {:?}", segment, decoder);
+                    x => unreachable!("{:?} unexpected {:?} {:?}", x, segment, one),
                 }
+            } else {
+                unreachable!("shouldn't get here {:?} {:?}", segment, decoder);
             }
         }
+
+        let mut s = 0;
+        for segment in &entry.outputs {
+            // decoder
+            s = decoder.get(segment).unwrap() + s * 10;
+        }
         total_sum += s;
     }
     total_sum
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(count_unique_outputs(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(sum_outputs(&parse(s)?))
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: usize = 8;
+
+    type Input = Vec<Entry>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        count_unique_outputs(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(sum_outputs(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
+    use crate::parse::ErrorKind;
 
     const INPUT: &str = r#"be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
 edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
@@ -317,21 +369,30 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
 
     #[test]
     fn test_day_8_example_part_1() {
-        assert_eq!(part_1(INPUT), 26);
+        assert_eq!(part_1(INPUT).unwrap(), 26);
     }
 
     #[test]
     fn test_day_8_part_1() {
-        assert_eq!(part_1(include_str!("input/day_8.txt")), 493);
+        assert_eq!(part_1(include_str!("input/day_8.txt")).unwrap(), 493);
     }
 
     #[test]
     fn test_day_8_example_part_2() {
-        assert_eq!(part_2(INPUT), 61229);
+        assert_eq!(part_2(INPUT).unwrap(), 61229);
     }
 
     #[test]
     fn test_day_8_part_2() {
-        assert_eq!(part_2(include_str!("input/day_8.txt")), 1010460);
+        assert_eq!(part_2(include_str!("input/day_8.txt")).unwrap(), 1010460);
+    }
+
+    #[test]
+    fn test_day_8_parse_error() {
+        let e = parse("be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb fdgacbe cefdb")
+            .unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (1, 1, ErrorKind::MissingField));
+        let e = parse(&INPUT.replace("cgb dgebacf", "cgb dgxbacf")).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 75, "x"));
     }
 }
@@ -90,89 +90,41 @@
 
 use std::collections::HashMap;
 
-fn adjacent_points(i: usize, j: usize, max_i: usize, max_j: usize) -> Vec<(usize, usize)> {
-    let mut res = vec![];
-    if i > 0 {
-        res.push((i - 1, j));
-    }
-    if j > 0 {
-        res.push((i, j - 1));
-    }
-    if i < max_i - 1 {
-        res.push((i + 1, j));
-    }
-    if j < max_j - 1 {
-        res.push((i, j + 1));
-    }
-    res
-}
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
-pub fn part_1(s: &str) -> usize {
-    let mut rows: Vec<Vec<u8>> = vec![];
-    for line in s.lines() {
-        let mut row = vec![];
-        for c in line.chars() {
-            row.push(c.to_digit(10).unwrap() as u8);
-        }
-        rows.push(row);
-    }
-
-    let mut sum_risk_levels = 0;
-    for i in 0..rows.len() {
-        for j in 0..rows[0].len() {
-            let v = rows[i][j];
-            if adjacent_points(i, j, rows.len(), rows[0].len())
-                .into_iter()
-                .all(|(ii, jj)| v < rows[ii][jj])
-            {
-                sum_risk_levels += (v as usize) + 1;
-            }
-        }
-    }
+pub fn parse(s: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse_digits(s)
+}
 
-    sum_risk_levels
+fn low_points(grid: &Grid<u8>) -> impl Iterator<Item = ((usize, usize), u8)> + '_ {
+    grid.iter()
+        .filter(move |(pos, v)| grid.neighbours_4(*pos).all(|n| **v < grid[n]))
+        .map(|(pos, v)| (pos, *v))
 }
 
-pub fn part_2(s: &str) -> usize {
-    let mut rows: Vec<Vec<u8>> = vec![];
-    for line in s.lines() {
-        let mut row = vec![];
-        for c in line.chars() {
-            row.push(c.to_digit(10).unwrap() as u8);
-        }
-        rows.push(row);
-    }
+fn sum_risk_levels(grid: &Grid<u8>) -> usize {
+    low_points(grid).map(|(_, v)| (v as usize) + 1).sum()
+}
 
-    let mut basins: Vec<Vec<usize>> = vec![];
-    for _ in 0..rows.len() {
-        basins.push((0..rows[0].len()).map(|_| 0).collect())
-    }
+fn basin_product(grid: &Grid<u8>) -> usize {
+    let mut basins = Grid::new(grid.width(), grid.height(), 0);
 
-    let mut basin_idx = 1;
-    let mut low_points = vec![];
-    for i in 0..rows.len() {
-        for j in 0..rows[0].len() {
-            let v = rows[i][j];
-            if adjacent_points(i, j, rows.len(), rows[0].len())
-                .into_iter()
-                .all(|(ii, jj)| v < rows[ii][jj])
-            {
-                low_points.push((i, j));
-                basins[i][j] = basin_idx;
-                basin_idx += 1;
-            }
-        }
+    let mut low_points = low_points(grid).map(|(pos, _)| pos).collect::<Vec<_>>();
+    for (basin_idx, pos) in (1..).zip(&low_points) {
+        basins[*pos] = basin_idx;
     }
 
-    while let Some((i, j)) = low_points.pop() {
-        for (n_i, n_j) in adjacent_points(i, j, rows.len(), rows[0].len()) {
-            if rows[n_i][n_j] > rows[i][j] && rows[n_i][n_j] != 9 {
+    while let Some(pos) = low_points.pop() {
+        for n in grid.neighbours_4(pos) {
+            if grid[n] > grid[pos] && grid[n] != 9 {
                 // add it to the basin
-                let v = basins[i][j];
-                if basins[n_i][n_j] == 0 {
-                    basins[n_i][n_j] = basins[i][j];
-                    low_points.push((n_i, n_j));
-                } else if basins[n_i][n_j] == v {
+                let v = basins[pos];
+                if basins[n] == 0 {
+                    basins[n] = basins[pos];
+                    low_points.push(n);
+                } else if basins[n] == v {
                     continue;
                 } else {
                     unreachable!()
@@ -182,11 +134,9 @@ pub fn part_2(s: &str) -> usize {
     }
 
     let mut basin_counts: HashMap<usize, usize> = HashMap::new();
-    for i in 0..basins.len() {
-        for j in 0..basins[0].len() {
-            if basins[i][j] != 0 {
-                *basin_counts.entry(basins[i][j]).or_default() += 1;
-            }
+    for (_, basin) in basins.iter() {
+        if *basin != 0 {
+            *basin_counts.entry(*basin).or_default() += 1;
         }
     }
 
@@ -198,9 +148,39 @@ pub fn part_2(s: &str) -> usize {
         * basin_count_values[basin_count_values.len() - 3]
 }
 
+pub fn part_1(s: &str) -> Result<usize, ParseError> {
+    Ok(sum_risk_levels(&parse(s)?))
+}
+
+pub fn part_2(s: &str) -> Result<usize, ParseError> {
+    Ok(basin_product(&parse(s)?))
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: usize = 9;
+
+    type Input = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse(s)
+    }
+
+    fn part_1(input: &Self::Input) -> Self::Answer1 {
+        sum_risk_levels(input)
+    }
+
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2> {
+        Some(basin_product(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{parse, part_1, part_2};
 
     const EXAMPLE: &str = r#"2199943210
 3987894921
@@ -210,21 +190,27 @@ mod tests {
 
     #[test]
     fn test_day_9_example_part_1() {
-        assert_eq!(part_1(EXAMPLE), 15);
+        assert_eq!(part_1(EXAMPLE).unwrap(), 15);
     }
 
     #[test]
     fn test_day_9_part_1() {
-        assert_eq!(part_1(include_str!("input/day_9.txt")), 585);
+        assert_eq!(part_1(include_str!("input/day_9.txt")).unwrap(), 585);
     }
 
     #[test]
     fn test_day_9_example_part_2() {
-        assert_eq!(part_2(EXAMPLE), 1134);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 1134);
     }
 
     #[test]
     fn test_day_9_part_2() {
-        assert_eq!(part_2(include_str!("input/day_9.txt")), 827904);
+        assert_eq!(part_2(include_str!("input/day_9.txt")).unwrap(), 827904);
+    }
+
+    #[test]
+    fn test_day_9_parse_error() {
+        let e = parse("2199\n39a7").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 3, "a"));
     }
 }
@@ -0,0 +1,379 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::solution::DynSolution;
+use crate::trace::{Silent, Stderr, Tracer};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct Queue {
+    jobs: VecDeque<Job>,
+    shutdown: bool,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    available: Condvar,
+}
+
+// The message a panic was raised with, when it's a string (as it is for
+// `panic!` and `expect`).
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(s) => (*s).to_owned(),
+        None => match payload.downcast_ref::<String>() {
+            Some(s) => s.clone(),
+            None => "unknown panic".to_owned(),
+        },
+    }
+}
+
+/// A fixed set of worker threads pulling jobs off a shared queue.
+pub struct Pool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+fn work(shared: &Shared) {
+    loop {
+        let job = {
+            let mut queue = shared.queue.lock().unwrap();
+            loop {
+                if let Some(job) = queue.jobs.pop_front() {
+                    break job;
+                }
+                if queue.shutdown {
+                    return;
+                }
+                queue = shared.available.wait(queue).unwrap();
+            }
+        };
+        // a panicking job shouldn't take the worker down with it; `map`
+        // passes the panic on to whoever is waiting for the result
+        let _ = panic::catch_unwind(AssertUnwindSafe(job));
+    }
+}
+
+impl Pool {
+    /// Starts `threads` workers (at least one).
+    pub fn new(threads: usize) -> Self {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                jobs: VecDeque::new(),
+                shutdown: false,
+            }),
+            available: Condvar::new(),
+        });
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || work(&shared))
+            })
+            .collect();
+        Pool { shared, workers }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn spawn(&self, job: impl FnOnce() + Send + 'static) {
+        self.shared
+            .queue
+            .lock()
+            .unwrap()
+            .jobs
+            .push_back(Box::new(job));
+        self.shared.available.notify_one();
+    }
+
+    /// Runs `f` on every item, returning the results in the same order. If
+    /// any call panics, so does `map`, once the rest have finished.
+    ///
+    /// While it waits, the calling thread runs this call's jobs itself, so
+    /// `map` can safely be called from inside another job on the same pool.
+    /// It never picks up anyone else's, so a job's running time is its own.
+    pub fn map<T, U, F>(&self, items: Vec<T>, f: F) -> Vec<U>
+    where
+        T: Send + 'static,
+        U: Send + 'static,
+        F: Fn(T) -> U + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let (tx, rx) = mpsc::channel();
+        let count = items.len();
+        // the jobs wait in a queue of their own; the shared queue only gets
+        // a ticket per job, for a worker to take whichever is next, if the
+        // calling thread hasn't already
+        let batch = Arc::new(Mutex::new(VecDeque::<Job>::new()));
+        for (i, item) in items.into_iter().enumerate() {
+            let f = Arc::clone(&f);
+            let tx = tx.clone();
+            batch.lock().unwrap().push_back(Box::new(move || {
+                let result = panic::catch_unwind(AssertUnwindSafe(|| f(item)));
+                let _ = tx.send((i, result));
+            }));
+        }
+        drop(tx);
+        for _ in 0..count {
+            let batch = Arc::clone(&batch);
+            self.spawn(move || {
+                let job = batch.lock().unwrap().pop_front();
+                if let Some(job) = job {
+                    job();
+                }
+            });
+        }
+
+        let mut results: Vec<Option<thread::Result<U>>> = (0..count).map(|_| None).collect();
+        let mut received = 0;
+        while received < count {
+            let (i, result) = match rx.try_recv() {
+                Ok(r) => r,
+                Err(_) => {
+                    let job = batch.lock().unwrap().pop_front();
+                    match job {
+                        Some(job) => {
+                            job();
+                            continue;
+                        }
+                        None => rx.recv().unwrap(),
+                    }
+                }
+            };
+            results[i] = Some(result);
+            received += 1;
+        }
+
+        results
+            .into_iter()
+            .map(|r| match r.unwrap() {
+                Ok(v) => v,
+                Err(e) => panic::resume_unwind(e),
+            })
+            .collect()
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        self.shared.queue.lock().unwrap().shutdown = true;
+        self.shared.available.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+static GLOBAL: OnceLock<Pool> = OnceLock::new();
+
+/// A pool shared by solvers that parallelise internally, sized to the
+/// machine unless `init_global` got there first.
+pub fn global() -> &'static Pool {
+    GLOBAL.get_or_init(|| Pool::new(thread::available_parallelism().map_or(4, |n| n.get())))
+}
+
+/// Starts the global pool with `threads` workers, unless it's already
+/// running. Returns the global pool either way.
+pub fn init_global(threads: usize) -> &'static Pool {
+    GLOBAL.get_or_init(|| Pool::new(threads))
+}
+
+#[derive(Clone, Debug)]
+pub struct PartRun {
+    pub part: usize,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct DayRun {
+    pub day: usize,
+    pub parse_time: Duration,
+    /// The answers to each part, or why the input couldn't be read or
+    /// parsed.
+    pub parts: Result<Vec<PartRun>, String>,
+}
+
+fn run_day(solution: &dyn DynSolution, input_dir: &Path, trace: bool) -> DayRun {
+    let day = solution.day();
+    let path = input_dir.join(format!("day_{}.txt", day));
+    let mut run = DayRun {
+        day,
+        parse_time: Duration::default(),
+        parts: Ok(vec![]),
+    };
+
+    let input = match fs::read_to_string(&path) {
+        Ok(input) => input,
+        Err(e) => {
+            run.parts = Err(format!("{}: {}", path.display(), e));
+            return run;
+        }
+    };
+    let start = Instant::now();
+    let parsed: Box<dyn Any> = match solution.parse(&input) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            run.parts = Err(format!("{}: {}", path.display(), e));
+            return run;
+        }
+    };
+    run.parse_time = start.elapsed();
+
+    let tracer: &mut dyn Tracer = if trace { &mut Stderr } else { &mut Silent };
+    let mut parts = vec![];
+    for part in 1..=2 {
+        let start = Instant::now();
+        if let Some(answer) = solution.part_traced(part, &*parsed, tracer) {
+            parts.push(PartRun {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            });
+        }
+    }
+    run.parts = Ok(parts);
+    run
+}
+
+/// Runs every day in `solutions` against `<input_dir>/day_N.txt`, each day
+/// as its own job on `pool`. Results come back in the order of `solutions`;
+/// a day whose solver panics reports the panic in place of its answers.
+pub fn run_days(
+    pool: &Pool,
+    solutions: &[&'static dyn DynSolution],
+    input_dir: &Path,
+    trace: bool,
+) -> Vec<DayRun> {
+    let input_dir = input_dir.to_owned();
+    pool.map(solutions.to_vec(), move |solution| {
+        panic::catch_unwind(AssertUnwindSafe(|| run_day(solution, &input_dir, trace)))
+            .unwrap_or_else(|e| DayRun {
+                day: solution.day(),
+                parse_time: Duration::default(),
+                parts: Err(format!("panicked: {}", panic_message(&*e))),
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+    use std::{env, fs, process};
+
+    use super::{run_days, Pool};
+    use crate::registry;
+
+    #[test]
+    fn test_pool_map() {
+        let pool = Pool::new(3);
+        assert_eq!(pool.threads(), 3);
+        assert_eq!(
+            pool.map((0..100).collect(), |v: u64| v * v),
+            (0..100).map(|v| v * v).collect::<Vec<_>>()
+        );
+
+        let ran = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..10 {
+            let (ran, tx) = (Arc::clone(&ran), tx.clone());
+            pool.spawn(move || {
+                ran.fetch_add(1, Ordering::SeqCst);
+                tx.send(()).unwrap();
+            });
+        }
+        rx.iter().take(10).for_each(drop);
+        assert_eq!(ran.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn test_pool_nested_map() {
+        // every worker ends up waiting on an inner map, which only finishes
+        // because the waiting threads pick up the inner jobs themselves
+        let pool = Arc::new(Pool::new(2));
+        let inner = Arc::clone(&pool);
+        let sums = pool.map((0..4).collect(), move |n: u32| {
+            inner.map((0..=n).collect(), |v: u32| v).iter().sum::<u32>()
+        });
+        assert_eq!(sums, vec![0, 1, 3, 6]);
+    }
+
+    #[test]
+    fn test_pool_map_runs_only_its_own_jobs() {
+        // with the only worker held up, the caller runs all of its map's jobs
+        // itself, and leaves the job queued ahead of them for the worker
+        let pool = Pool::new(1);
+        let (release, held) = mpsc::channel::<()>();
+        pool.spawn(move || held.recv().unwrap());
+        let (tx, rx) = mpsc::channel();
+        pool.spawn(move || tx.send(thread::current().id()).unwrap());
+
+        let caller = thread::current().id();
+        let ran_on = pool.map((0..4).collect(), |_: u32| thread::current().id());
+        assert!(ran_on.iter().all(|id| *id == caller));
+        assert!(rx.try_recv().is_err());
+
+        release.send(()).unwrap();
+        assert_ne!(rx.recv().unwrap(), caller);
+    }
+
+    #[test]
+    #[should_panic(expected = "bad item")]
+    fn test_pool_map_panics() {
+        let pool = Pool::new(2);
+        pool.map(vec![1, 2, 3], |v: u32| {
+            if v == 2 {
+                panic!("bad item");
+            }
+            v
+        });
+    }
+
+    #[test]
+    fn test_run_days() {
+        let solutions = [1, 2, 25].map(|day| registry::get(day).unwrap());
+        let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/input");
+        let runs = run_days(&Pool::new(2), &solutions, &input_dir, false);
+        assert_eq!(
+            runs.iter().map(|r| r.day).collect::<Vec<_>>(),
+            vec![1, 2, 25]
+        );
+        let answers = runs[0].parts.as_ref().unwrap();
+        assert_eq!(
+            answers
+                .iter()
+                .map(|p| p.answer.as_str())
+                .collect::<Vec<_>>(),
+            vec!["1184", "1158"]
+        );
+        assert_eq!(runs[2].parts.as_ref().unwrap().len(), 1);
+
+        let runs = run_days(&Pool::new(1), &solutions[..1], Path::new("missing"), false);
+        assert!(runs[0].parts.is_err());
+
+        // a board that never wins, so day 4 panics, but day 1 still runs
+        let dir = env::temp_dir().join(format!("run_days_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day_4.txt"), "1\n\n2").unwrap();
+        fs::copy(input_dir.join("day_1.txt"), dir.join("day_1.txt")).unwrap();
+        let solutions = [4, 1].map(|day| registry::get(day).unwrap());
+        let runs = run_days(&Pool::new(2), &solutions, &dir, false);
+        assert_eq!(
+            runs[0].parts.as_ref().unwrap_err(),
+            "panicked: no board wins"
+        );
+        assert_eq!(runs[1].parts.as_ref().unwrap().len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,1020 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+use crate::grid::{Grid, Pos};
+
+/// A small seeded generator (SplitMix64). Not suitable for anything but
+/// making up puzzle inputs, but it's deterministic across platforms, so a
+/// seed is enough to reproduce an input.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`. Panics if `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        // reject the top sliver so that every value is equally likely
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let v = self.next_u64();
+            if v < zone {
+                return v % n;
+            }
+        }
+    }
+
+    /// Uniform in `lo..=hi`.
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        assert!(lo <= hi, "empty range");
+        lo + self.below((hi - lo) as u64 + 1) as i64
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// True with probability `num / den`.
+    pub fn chance(&mut self, num: u64, den: u64) -> bool {
+        self.below(den) < num
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+type Generator = fn(&mut Rng, usize) -> String;
+
+const GENERATORS: [Generator; 25] = [
+    day_1, day_2, day_3, day_4, day_5, day_6, day_7, day_8, day_9, day_10, day_11, day_12, day_13,
+    day_14, day_15, day_16, day_17, day_18, day_19, day_20, day_21, day_22, day_23, day_24, day_25,
+];
+
+/// Makes up a valid input for `day`. What `size` controls depends on the day
+/// (see the generator for each one); days 21, 23 and 24 have a fixed shape
+/// and ignore it.
+pub fn generate(day: usize, seed: u64, size: usize) -> Option<String> {
+    let generator = GENERATORS.get(day.checked_sub(1)?)?;
+    Some(generator(&mut Rng::new(seed), size))
+}
+
+fn join<T: ToString>(values: impl IntoIterator<Item = T>, sep: &str) -> String {
+    values
+        .into_iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+// `size` depth readings.
+pub fn day_1(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    let mut depth = rng.range(100, 200);
+    for _ in 0..size.max(1) {
+        writeln!(out, "{}", depth).unwrap();
+        depth = (depth + rng.range(-10, 20)).max(0);
+    }
+    out
+}
+
+// `size` commands.
+pub fn day_2(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size.max(1) {
+        let action = rng.choose(&["forward", "down", "up"]);
+        writeln!(out, "{} {}", action, rng.range(1, 9)).unwrap();
+    }
+    out
+}
+
+// Simulates day 3's life support filter, which needs to narrow the rows down
+// to exactly one without ever discarding all of them.
+fn narrows_to_one(rows: &[Vec<bool>], most_common: bool) -> bool {
+    let mut rows = rows.iter().collect::<Vec<_>>();
+    for idx in 0..rows[0].len() {
+        if rows.len() > 1 {
+            let ones = rows.iter().filter(|r| r[idx]).count();
+            let keep = (ones * 2 >= rows.len()) == most_common;
+            rows.retain(|r| r[idx] == keep);
+        }
+    }
+    rows.len() == 1
+}
+
+/// `count` different rows of `width` bits, for which both of day 3's ratings
+/// exist. Panics if `count` is zero or more than `width` bits can tell apart.
+pub fn bit_report(rng: &mut Rng, count: usize, width: usize) -> String {
+    assert!(count > 0, "a report needs at least one row");
+    assert!(
+        width >= usize::BITS as usize || count <= 1 << width,
+        "{} bits only make {} different rows",
+        width,
+        1usize << width
+    );
+    loop {
+        let mut rows = BTreeSet::new();
+        while rows.len() < count {
+            rows.insert((0..width).map(|_| rng.chance(1, 2)).collect::<Vec<_>>());
+        }
+        let mut rows = rows.into_iter().collect::<Vec<_>>();
+        rng.shuffle(&mut rows);
+
+        if narrows_to_one(&rows, true) && narrows_to_one(&rows, false) {
+            let mut out = String::new();
+            for row in rows {
+                let row = row.iter().map(|b| if *b { '1' } else { '0' });
+                writeln!(out, "{}", row.collect::<String>()).unwrap();
+            }
+            return out;
+        }
+    }
+}
+
+// `size` rows (rounded up to an odd number, so that no bit is ever tied).
+pub fn day_3(rng: &mut Rng, size: usize) -> String {
+    let count = size | 1;
+    let width = (64 - count.leading_zeros() as usize + 2).max(5);
+    bit_report(rng, count, width)
+}
+
+// `size` boards. Every number from 0 to 99 is drawn, so every board wins
+// eventually.
+pub fn day_4(rng: &mut Rng, size: usize) -> String {
+    let mut numbers = (0..100).collect::<Vec<u8>>();
+    rng.shuffle(&mut numbers);
+    let mut out = join(&numbers, ",");
+    out.push('\n');
+
+    for _ in 0..size.max(1) {
+        rng.shuffle(&mut numbers);
+        out.push('\n');
+        for row in numbers[..25].chunks(5) {
+            let row = row.iter().map(|n| format!("{:>2}", n)).collect::<Vec<_>>();
+            writeln!(out, "{}", row.join(" ")).unwrap();
+        }
+    }
+    out
+}
+
+// `size` lines of vents, each horizontal, vertical or diagonal.
+pub fn day_5(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size.max(1) {
+        let (x1, y1) = (rng.range(0, 999), rng.range(0, 999));
+        let (dx, dy) = match rng.below(3) {
+            0 => (*rng.choose(&[-1, 1]), 0),
+            1 => (0, *rng.choose(&[-1, 1])),
+            _ => (*rng.choose(&[-1, 1]), *rng.choose(&[-1, 1])),
+        };
+        // keep both ends on the 1000x1000 floor
+        let room = |v: i64, d: i64| match d {
+            1 => 999 - v,
+            -1 => v,
+            _ => i64::MAX,
+        };
+        let len = rng.range(0, 300).min(room(x1, dx)).min(room(y1, dy));
+        writeln!(out, "{},{} -> {},{}", x1, y1, x1 + dx * len, y1 + dy * len).unwrap();
+    }
+    out
+}
+
+// `size` lanternfish.
+pub fn day_6(rng: &mut Rng, size: usize) -> String {
+    let fish = (0..size.max(1)).map(|_| rng.range(1, 5));
+    join(fish, ",") + "\n"
+}
+
+// `size` crabs.
+pub fn day_7(rng: &mut Rng, size: usize) -> String {
+    let crabs = (0..size.max(1)).map(|_| rng.range(0, 1000) * rng.range(0, 1000) / 500);
+    join(crabs, ",") + "\n"
+}
+
+const SEGMENTS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+fn signal(rng: &mut Rng, wiring: &[char], digit: usize) -> String {
+    let mut wires = SEGMENTS[digit]
+        .chars()
+        .map(|c| wiring[c as usize - 'a' as usize])
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut wires);
+    wires.into_iter().collect()
+}
+
+// `size` displays, each wired up differently.
+pub fn day_8(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size.max(1) {
+        let mut wiring = ('a'..='g').collect::<Vec<_>>();
+        rng.shuffle(&mut wiring);
+        let mut digits = (0..10).collect::<Vec<_>>();
+        rng.shuffle(&mut digits);
+        digits.extend((0..4).map(|_| rng.index(10)));
+
+        let signals = digits
+            .into_iter()
+            .map(|d| signal(rng, &wiring, d))
+            .collect::<Vec<_>>();
+        writeln!(
+            out,
+            "{} | {}",
+            signals[..10].join(" "),
+            signals[10..].join(" ")
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn manhattan(a: Pos, b: Pos) -> usize {
+    a.0.max(b.0) - a.0.min(b.0) + a.1.max(b.1) - a.1.min(b.1)
+}
+
+// A `size`x`size` height map. Day 9's basin fill only ever climbs, so each
+// basin is a region walled off by 9s whose heights rise with the distance
+// from its one low point.
+pub fn day_9(rng: &mut Rng, size: usize) -> String {
+    let side = size.max(4);
+    loop {
+        let seeds = (0..(side * side / 16).max(3))
+            .map(|_| (rng.index(side), rng.index(side)))
+            .collect::<Vec<_>>();
+        let region = Grid::from_fn(side, side, |pos| {
+            (0..seeds.len())
+                .min_by_key(|s| manhattan(pos, seeds[*s]))
+                .unwrap()
+        });
+        // a cell that borders another region to its right or below becomes a
+        // wall, which is enough to keep every pair of regions apart
+        let wall = Grid::from_fn(side, side, |pos| {
+            [(0, 1), (1, 0)].iter().any(|d| {
+                region
+                    .offset(pos, *d)
+                    .is_some_and(|n| region[n] != region[pos])
+            })
+        });
+
+        let mut height = Grid::new(side, side, 9u8);
+        let mut basins = 0;
+        for (s, &seed) in seeds.iter().enumerate() {
+            if region[seed] != s || wall[seed] {
+                continue;
+            }
+            basins += 1;
+            height[seed] = 0;
+            let mut frontier = vec![seed];
+            for h in 1.. {
+                let mut next = vec![];
+                for pos in frontier {
+                    for n in height.neighbours_4(pos) {
+                        if region[n] == s && !wall[n] && height[n] == 9 && n != seed {
+                            height[n] = h.min(8);
+                            next.push(n);
+                        }
+                    }
+                }
+                if next.is_empty() {
+                    break;
+                }
+                frontier = next;
+            }
+        }
+
+        if basins >= 3 {
+            return height.to_string() + "\n";
+        }
+    }
+}
+
+const OPEN: [char; 4] = ['(', '[', '{', '<'];
+const CLOSE: [char; 4] = [')', ']', '}', '>'];
+
+fn chunk_line(rng: &mut Rng, corrupted: bool) -> String {
+    let mut line = String::new();
+    let mut open = vec![];
+    for _ in 0..rng.range(10, 40) {
+        if open.is_empty() || rng.chance(3, 5) {
+            let i = rng.index(4);
+            line.push(OPEN[i]);
+            open.push(i);
+        } else {
+            line.push(CLOSE[open.pop().unwrap()]);
+        }
+    }
+    if open.is_empty() {
+        let i = rng.index(4);
+        line.push(OPEN[i]);
+        open.push(i);
+    }
+    if corrupted {
+        let expected = *open.last().unwrap();
+        let wrong = (expected + 1 + rng.index(3)) % 4;
+        line.push(CLOSE[wrong]);
+        for _ in 0..rng.range(0, 5) {
+            line.push(OPEN[rng.index(4)]);
+        }
+    }
+    line
+}
+
+// `size` lines, each corrupted or incomplete. There's always an odd number of
+// incomplete ones, so that part 2 has a middle score.
+pub fn day_10(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(1);
+    let mut corrupted = (0..count).map(|_| rng.chance(1, 2)).collect::<Vec<_>>();
+    if corrupted.iter().filter(|c| !**c).count() % 2 == 0 {
+        let i = rng.index(count);
+        corrupted[i] = !corrupted[i];
+    }
+
+    let mut out = String::new();
+    for c in corrupted {
+        writeln!(out, "{}", chunk_line(rng, c)).unwrap();
+    }
+    out
+}
+
+// A `size`x`size` grid of octopuses. Not every grid ever flashes all at once,
+// so they're checked up front.
+pub fn day_11(rng: &mut Rng, size: usize) -> String {
+    let side = size.max(2);
+    loop {
+        let grid = Grid::from_fn(side, side, |_| rng.below(10) as u8);
+        let mut octopuses = grid.clone();
+        if (0..1000).any(|_| crate::day_11::step(&mut octopuses) == grid.len()) {
+            return grid.to_string() + "\n";
+        }
+    }
+}
+
+fn cave_name(rng: &mut Rng, big: bool, taken: &mut HashSet<String>) -> String {
+    loop {
+        let base = if big { b'A' } else { b'a' };
+        let name = (0..2)
+            .map(|_| (base + rng.below(26) as u8) as char)
+            .collect::<String>();
+        if taken.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+// A cave system with `size` small caves. Big caves are never connected to
+// each other, since there would be infinitely many paths.
+pub fn day_12(rng: &mut Rng, size: usize) -> String {
+    let mut taken = HashSet::new();
+    let small = (0..size.max(1))
+        .map(|_| cave_name(rng, false, &mut taken))
+        .collect::<Vec<_>>();
+    let big = (0..(size / 3).max(1))
+        .map(|_| cave_name(rng, true, &mut taken))
+        .collect::<Vec<_>>();
+
+    let mut edges = BTreeSet::new();
+    let mut connect = |a: &str, b: &str| {
+        if a != b {
+            edges.insert((a.min(b).to_owned(), a.max(b).to_owned()));
+        }
+    };
+    for _ in 0..2 {
+        connect("start", rng.choose(&small[..]).as_str());
+        connect(rng.choose(&small[..]).as_str(), "end");
+    }
+    connect("start", rng.choose(&big[..]).as_str());
+    for cave in &small {
+        if rng.chance(2, 3) {
+            connect(cave, rng.choose(&big[..]).as_str());
+        }
+        if rng.chance(1, 2) {
+            connect(cave, rng.choose(&small[..]).as_str());
+        }
+    }
+
+    let mut out = String::new();
+    for (a, b) in edges {
+        writeln!(out, "{}-{}", a, b).unwrap();
+    }
+    out
+}
+
+// `size` dots on a sheet that's folded one to three times. No dot ever sits
+// on a fold line.
+pub fn day_13(rng: &mut Rng, size: usize) -> String {
+    // work outwards from the folded-up sheet
+    let (mut width, mut height) = (rng.range(5, 40), rng.range(5, 10));
+    let mut folds = vec![];
+    for _ in 0..rng.range(1, 3) {
+        if rng.chance(1, 2) {
+            folds.push(('x', width));
+            width = width * 2 + 1;
+        } else {
+            folds.push(('y', height));
+            height = height * 2 + 1;
+        }
+    }
+    folds.reverse();
+
+    let on_fold = |axis: char, v: i64| folds.contains(&(axis, v));
+    let mut dots = BTreeSet::new();
+    let count = size.clamp(1, (width * height / 2) as usize);
+    while dots.len() < count {
+        let (x, y) = (rng.range(0, width - 1), rng.range(0, height - 1));
+        if !on_fold('x', x) && !on_fold('y', y) {
+            dots.insert((x, y));
+        }
+    }
+
+    let mut out = String::new();
+    for (x, y) in dots {
+        writeln!(out, "{},{}", x, y).unwrap();
+    }
+    out.push('\n');
+    for (axis, v) in folds {
+        writeln!(out, "fold along {}={}", axis, v).unwrap();
+    }
+    out
+}
+
+// A template `size` elements long, with a rule for every pair.
+pub fn day_14(rng: &mut Rng, size: usize) -> String {
+    let elements = ['B', 'C', 'F', 'H', 'K', 'N', 'O', 'P', 'S', 'V'];
+    let template = (0..size.max(2))
+        .map(|_| *rng.choose(&elements))
+        .collect::<String>();
+
+    let mut out = template + "\n\n";
+    for a in elements {
+        for b in elements {
+            writeln!(out, "{}{} -> {}", a, b, rng.choose(&elements)).unwrap();
+        }
+    }
+    out
+}
+
+// A `size`x`size` risk map.
+pub fn day_15(rng: &mut Rng, size: usize) -> String {
+    let side = size.max(2);
+    Grid::from_fn(side, side, |_| rng.range(1, 9)).to_string() + "\n"
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Packet {
+    Literal {
+        version: u8,
+        value: u64,
+    },
+    Operator {
+        version: u8,
+        type_id: u8,
+        operands: Vec<Packet>,
+        // length type 1; otherwise the operands are given as a bit length
+        counted: bool,
+    },
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: u64, width: usize) {
+    bits.extend((0..width).rev().map(|i| value >> i & 1 == 1));
+}
+
+impl Packet {
+    /// A random packet made of `size` packets in total. Every value along the
+    /// way fits in 32 bits.
+    pub fn random(rng: &mut Rng, size: usize) -> Packet {
+        let version = rng.below(8) as u8;
+        if size <= 1 {
+            let digits = rng.range(1, 4) as u64;
+            return Packet::Literal {
+                version,
+                value: rng.below(1 << (4 * digits)),
+            };
+        }
+
+        let budget = size - 1;
+        let comparison = budget >= 2 && rng.chance(1, 4);
+        let (mut type_id, count) = if comparison {
+            (rng.range(5, 7) as u8, 2)
+        } else {
+            (rng.range(0, 3) as u8, 1 + rng.index(budget.min(4)))
+        };
+
+        // every operand gets at least one packet, the rest are spread out
+        let mut sizes = vec![1; count];
+        for _ in count..budget {
+            sizes[rng.index(count)] += 1;
+        }
+        let operands = sizes
+            .into_iter()
+            .map(|s| Packet::random(rng, s))
+            .collect::<Vec<_>>();
+
+        let mut values = operands.iter().map(Packet::value);
+        let too_big = match type_id {
+            0 => values.sum::<u64>() > u32::MAX as u64,
+            1 => values
+                .try_fold(1u64, |acc, v| acc.checked_mul(v))
+                .is_none_or(|v| v > u32::MAX as u64),
+            _ => false,
+        };
+        if too_big {
+            type_id = 3;
+        }
+
+        Packet::Operator {
+            version,
+            type_id,
+            operands,
+            counted: rng.chance(1, 2),
+        }
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        match self {
+            Packet::Literal { version, .. } => *version as u64,
+            Packet::Operator {
+                version, operands, ..
+            } => *version as u64 + operands.iter().map(Packet::version_sum).sum::<u64>(),
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        let (type_id, operands) = match self {
+            Packet::Literal { value, .. } => return *value,
+            Packet::Operator {
+                type_id, operands, ..
+            } => (*type_id, operands),
+        };
+        let mut values = operands.iter().map(Packet::value);
+        match type_id {
+            0 => values.sum(),
+            1 => values.product(),
+            2 => values.min().unwrap(),
+            3 => values.max().unwrap(),
+            _ => {
+                let (a, b) = (values.next().unwrap(), values.next().unwrap());
+                let v = match type_id {
+                    5 => a > b,
+                    6 => a < b,
+                    _ => a == b,
+                };
+                v as u64
+            }
+        }
+    }
+
+    fn encode(&self, bits: &mut Vec<bool>) {
+        match self {
+            Packet::Literal { version, value } => {
+                push_bits(bits, *version as u64, 3);
+                push_bits(bits, 4, 3);
+                let groups = (64 - value.leading_zeros() as usize).div_ceil(4).max(1);
+                for g in (0..groups).rev() {
+                    bits.push(g != 0);
+                    push_bits(bits, value >> (4 * g) & 0xf, 4);
+                }
+            }
+            Packet::Operator {
+                version,
+                type_id,
+                operands,
+                counted,
+            } => {
+                push_bits(bits, *version as u64, 3);
+                push_bits(bits, *type_id as u64, 3);
+                let mut sub = vec![];
+                for operand in operands {
+                    operand.encode(&mut sub);
+                }
+                if *counted || sub.len() >= 1 << 15 {
+                    bits.push(true);
+                    push_bits(bits, operands.len() as u64, 11);
+                } else {
+                    bits.push(false);
+                    push_bits(bits, sub.len() as u64, 15);
+                }
+                bits.extend(sub);
+            }
+        }
+    }
+
+    /// The packet as a hex transmission, padded with zeroes.
+    pub fn to_hex(&self) -> String {
+        let mut bits = vec![];
+        self.encode(&mut bits);
+        bits.chunks(4)
+            .map(|nibble| {
+                let v = (0..4).fold(0, |acc, i| {
+                    acc << 1 | *nibble.get(i).unwrap_or(&false) as u32
+                });
+                std::char::from_digit(v, 16).unwrap().to_ascii_uppercase()
+            })
+            .collect()
+    }
+}
+
+// A transmission of `size` packets.
+pub fn day_16(rng: &mut Rng, size: usize) -> String {
+    Packet::random(rng, size.max(1)).to_hex() + "\n"
+}
+
+// A target about `size` wide, kept within the velocities day 17 searches.
+pub fn day_17(rng: &mut Rng, size: usize) -> String {
+    let width = size.clamp(1, 100) as i64;
+    let min_x = rng.range(15, 290 - width);
+    let height = rng.range(1, 40);
+    let min_y = rng.range(-99, -2 - height);
+    format!(
+        "target area: x={}..{}, y={}..{}\n",
+        min_x,
+        min_x + width,
+        min_y,
+        min_y + height
+    )
+}
+
+fn snailfish_number(rng: &mut Rng, depth: usize, out: &mut String) {
+    if depth > 0 && (depth == 4 || rng.chance(1, 3)) {
+        write!(out, "{}", rng.range(0, 9)).unwrap();
+    } else {
+        out.push('[');
+        snailfish_number(rng, depth + 1, out);
+        out.push(',');
+        snailfish_number(rng, depth + 1, out);
+        out.push(']');
+    }
+}
+
+// `size` reduced snailfish numbers.
+pub fn day_18(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size.max(2) {
+        snailfish_number(rng, 0, &mut out);
+        out.push('\n');
+    }
+    out
+}
+
+type Vec3 = [isize; 3];
+type Mat3 = [[isize; 3]; 3];
+
+/// A scanner along with where it really is: `beacons` are relative to the
+/// scanner and seen through `rotation`, so a beacon at `p` in scanner 0's
+/// frame shows up at `rotation * (p - position)`.
+#[derive(Clone, Debug)]
+pub struct Scanner {
+    pub position: Vec3,
+    pub rotation: Mat3,
+    pub beacons: Vec<Vec3>,
+}
+
+impl Scanner {
+    /// Maps one of `beacons` back into scanner 0's frame.
+    pub fn to_world(&self, beacon: Vec3) -> Vec3 {
+        // rotations are orthogonal, so the transpose undoes them
+        let mut p = self.position;
+        for (i, v) in p.iter_mut().enumerate() {
+            *v += (0..3)
+                .map(|k| self.rotation[k][i] * beacon[k])
+                .sum::<isize>();
+        }
+        p
+    }
+}
+
+fn proper_rotations() -> Vec<Mat3> {
+    let det = |m: &Mat3| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let rotations = crate::day_19::rotations()
+        .filter(|m| det(m) == 1)
+        .collect::<BTreeSet<_>>();
+    rotations.into_iter().collect()
+}
+
+/// `count` scanners in a chain, each sharing 16 beacons with the one before
+/// it. Scanner 0 sits at the origin and isn't rotated.
+pub fn scanners(rng: &mut Rng, count: usize) -> Vec<Scanner> {
+    let rotations = proper_rotations();
+    let mut used = HashSet::new();
+    let mut point = |rng: &mut Rng, around: Vec3| loop {
+        let p = [0, 1, 2].map(|i| around[i] + rng.range(-1000, 1000) as isize);
+        if used.insert(p) {
+            return p;
+        }
+    };
+
+    let mut positions: Vec<Vec3> = vec![[0, 0, 0]];
+    let mut world: Vec<Vec<Vec3>> = vec![vec![]];
+    for i in 1..count.max(1) {
+        let prev = positions[i - 1];
+        let position = [0, 1, 2].map(|k| prev[k] + rng.range(-1200, 1200) as isize);
+        let middle = [0, 1, 2].map(|k| (prev[k] + position[k]) / 2);
+        let shared = (0..16).map(|_| point(rng, middle)).collect::<Vec<_>>();
+        world[i - 1].extend(&shared);
+        world.push(shared);
+        positions.push(position);
+    }
+    for (beacons, position) in world.iter_mut().zip(&positions) {
+        for _ in 0..10 {
+            beacons.push(point(rng, *position));
+        }
+        rng.shuffle(beacons);
+    }
+
+    positions
+        .into_iter()
+        .zip(world)
+        .enumerate()
+        .map(|(i, (position, world))| {
+            let rotation = if i == 0 {
+                [[1, 0, 0], [0, 1, 0], [0, 0, 1]]
+            } else {
+                *rng.choose(&rotations)
+            };
+            let beacons = world
+                .into_iter()
+                .map(|p| {
+                    let d = [0, 1, 2].map(|k| p[k] - position[k]);
+                    [0, 1, 2].map(|k| (0..3).map(|j| rotation[k][j] * d[j]).sum())
+                })
+                .collect();
+            Scanner {
+                position,
+                rotation,
+                beacons,
+            }
+        })
+        .collect()
+}
+
+pub fn format_scanners(scanners: &[Scanner]) -> String {
+    let mut out = String::new();
+    for (i, scanner) in scanners.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        writeln!(out, "--- scanner {} ---", i).unwrap();
+        for b in &scanner.beacons {
+            writeln!(out, "{},{},{}", b[0], b[1], b[2]).unwrap();
+        }
+    }
+    out
+}
+
+// `size` scanners.
+pub fn day_19(rng: &mut Rng, size: usize) -> String {
+    format_scanners(&scanners(rng, size.max(2)))
+}
+
+// A `size`x`size` image. When the algorithm lights up dark areas it also
+// turns lit areas dark, or the infinite image would end up fully lit.
+pub fn day_20(rng: &mut Rng, size: usize) -> String {
+    let mut algo = (0..512).map(|_| rng.chance(1, 2)).collect::<Vec<_>>();
+    if algo[0] {
+        algo[511] = false;
+    }
+    let pixel = |lit: bool| if lit { '#' } else { '.' };
+    let side = size.max(1);
+    let image = Grid::from_fn(side, side, |_| pixel(rng.chance(1, 2)));
+
+    let mut out = algo.into_iter().map(pixel).collect::<String>();
+    write!(out, "\n\n{}\n", image).unwrap();
+    out
+}
+
+pub fn day_21(rng: &mut Rng, _size: usize) -> String {
+    format!(
+        "Player 1 starting position: {}\nPlayer 2 starting position: {}\n",
+        rng.range(1, 10),
+        rng.range(1, 10)
+    )
+}
+
+// `size` reboot steps. The first half stay within the initialization area,
+// like the real input's do.
+pub fn day_22(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(1);
+    let mut out = String::new();
+    for i in 0..count {
+        let (bound, extent) = if i < count.div_ceil(2) {
+            (50, 30)
+        } else {
+            (100_000, 50_000)
+        };
+        let on = i == 0 || rng.chance(2, 3);
+        let ranges = ['x', 'y', 'z'].map(|axis| {
+            let min = rng.range(-bound, bound - 1);
+            let max = (min + rng.range(0, extent)).min(bound);
+            format!("{}={}..{}", axis, min, max)
+        });
+        let state = if on { "on" } else { "off" };
+        writeln!(out, "{} {}", state, ranges.join(",")).unwrap();
+    }
+    out
+}
+
+// The usual burrow, with the amphipods shuffled between the rooms.
+pub fn day_23(rng: &mut Rng, _size: usize) -> String {
+    let mut amphipods = ['A', 'A', 'B', 'B', 'C', 'C', 'D', 'D'];
+    rng.shuffle(&mut amphipods);
+    let [a, b, c, d, e, f, g, h] = amphipods;
+    format!(
+        "#############\n#...........#\n###{}#{}#{}#{}###\n  #{}#{}#{}#{}#\n  #########\n",
+        a, b, c, d, e, f, g, h
+    )
+}
+
+// A MONAD program: seven blocks push a digit onto `z` (as a base 26 stack) and
+// seven pop one off again, which only leaves `z` at zero when every popped
+// digit is within 8 of the one pushed with it.
+pub fn day_24(rng: &mut Rng, _size: usize) -> String {
+    let mut out = String::new();
+    let mut stack = vec![];
+    let mut pushes = 7;
+    for _ in 0..14 {
+        let push = pushes > 0 && (stack.is_empty() || rng.chance(1, 2));
+        let (div, add_x) = if push {
+            pushes -= 1;
+            (1, rng.range(10, 16))
+        } else {
+            let pushed: i64 = stack.pop().unwrap();
+            (26, rng.range(-8, 8) - pushed)
+        };
+        let add_y = rng.range(1, 16);
+        if push {
+            stack.push(add_y);
+        }
+        write!(
+            out,
+            "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\n\
+             mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {}\n\
+             mul y x\nadd z y\n",
+            div, add_x, add_y
+        )
+        .unwrap();
+    }
+    out
+}
+
+// A `size`x`size` sea floor. The herds have to come to a stop eventually,
+// which isn't a given, so that's checked up front.
+pub fn day_25(rng: &mut Rng, size: usize) -> String {
+    let side = size.max(2);
+    loop {
+        let grid = Grid::from_fn(side, side, |_| match rng.below(8) {
+            0..=2 => '>',
+            3..=5 => 'v',
+            _ => '.',
+        });
+        let mut herd = grid.clone();
+        if (0..side * side * 4).any(|_| crate::day_25::step(&mut herd) == 0) {
+            return grid.to_string() + "\n";
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{bit_report, format_scanners, generate, scanners, Packet, Rng};
+    use crate::registry;
+
+    #[test]
+    fn test_rng() {
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        assert_eq!(
+            (0..10).map(|_| a.next_u64()).collect::<Vec<_>>(),
+            (0..10).map(|_| b.next_u64()).collect::<Vec<_>>()
+        );
+        assert!((0..1000)
+            .map(|_| a.range(-3, 3))
+            .all(|v| (-3..=3).contains(&v)));
+        let mut items = (0..20).collect::<Vec<_>>();
+        a.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+        assert_eq!(generate(22, 3, 10), generate(22, 3, 10));
+        assert_eq!(generate(26, 3, 10), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "2 bits only make 4 different rows")]
+    fn test_bit_report_too_many_rows() {
+        // every row there is still works
+        assert_eq!(bit_report(&mut Rng::new(3), 4, 2).lines().count(), 4);
+        bit_report(&mut Rng::new(3), 5, 2);
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        // days 19, 21 and 23 take seconds to solve even small inputs
+        for solution in registry::SOLUTIONS {
+            let day = solution.day();
+            if [19, 21, 23].contains(&day) {
+                continue;
+            }
+            for seed in 0..3 {
+                let input = generate(day, seed, 6).unwrap();
+                let parsed = solution
+                    .parse(&input)
+                    .unwrap_or_else(|e| panic!("day {} seed {}: {}\n{}", day, seed, e, input));
+                solution.part(1, &*parsed);
+                solution.part(2, &*parsed);
+            }
+        }
+
+        let day_21 = registry::get(21).unwrap();
+        assert!(day_21.parse(&generate(21, 0, 0).unwrap()).is_ok());
+        // a seed whose burrow happens to be quick to sort out
+        let day_23 = registry::get(23).unwrap();
+        let parsed = day_23.parse(&generate(23, 2, 0).unwrap()).unwrap();
+        day_23.part(1, &*parsed);
+    }
+
+    #[test]
+    fn test_generated_packets() {
+        let mut rng = Rng::new(16);
+        for size in [1, 2, 10, 50] {
+            let packet = Packet::random(&mut rng, size);
+            let hex = packet.to_hex();
+            assert_eq!(
+                crate::day_16::part_1(&hex).unwrap() as u64,
+                packet.version_sum()
+            );
+            assert_eq!(crate::day_16::part_2(&hex).unwrap() as u64, packet.value());
+        }
+    }
+
+    #[test]
+    fn test_generated_scanners() {
+        let scanners = scanners(&mut Rng::new(19), 3);
+        let beacons = scanners
+            .iter()
+            .flat_map(|s| s.beacons.iter().map(move |b| s.to_world(*b)))
+            .collect::<HashSet<_>>();
+        let distance = scanners
+            .iter()
+            .flat_map(|a| {
+                scanners.iter().map(move |b| {
+                    (0..3)
+                        .map(|k| (a.position[k] - b.position[k]).abs())
+                        .sum::<isize>()
+                })
+            })
+            .max()
+            .unwrap();
+
+        let input = format_scanners(&scanners);
+        assert_eq!(crate::day_19::part_1(&input).unwrap(), beacons.len());
+        assert_eq!(crate::day_19::part_2(&input).unwrap(), distance);
+    }
+
+    #[test]
+    fn test_generated_reboot_steps() {
+        // light up the initialization area one cube at a time
+        let steps = crate::day_22::parse(&generate(22, 22, 20).unwrap()).unwrap();
+        let mut cubes = vec![false; 101 * 101 * 101];
+        for step in &steps {
+            let r = step.ranges;
+            let clip = |min: isize, max: isize| min.max(-50)..=max.min(50);
+            for x in clip(r.x_min, r.x_max) {
+                for y in clip(r.y_min, r.y_max) {
+                    for z in clip(r.z_min, r.z_max) {
+                        let i = ((x + 50) * 101 + y + 50) * 101 + z + 50;
+                        cubes[i as usize] = step.on;
+                    }
+                }
+            }
+        }
+        let lit = cubes.iter().filter(|c| **c).count() as isize;
+        assert_eq!(
+            crate::day_22::part_1(&generate(22, 22, 20).unwrap()).unwrap(),
+            lit
+        );
+    }
+}
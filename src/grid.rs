@@ -0,0 +1,280 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::parse::{self, ErrorKind, ParseError};
+
+/// Positions are `(row, column)`, with `(0, 0)` in the top-left corner.
+pub type Pos = (usize, usize);
+
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (1, 0), (0, 1)];
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A dense, rectangular grid stored row by row.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|i| (0..width).map(move |j| (i, j)))
+            .map(&mut f)
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses one row per line, turning each character into a cell with
+    /// `cell`. Characters it rejects are reported as unexpected.
+    pub fn parse(s: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut lines = parse::lines(s);
+        let first = lines.expect()?;
+
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = vec![];
+        for line in std::iter::once(first).chain(lines) {
+            let start = cells.len();
+            for (c, at) in line.chars() {
+                cells.push(cell(c).ok_or_else(|| line.error(at, ErrorKind::UnexpectedChar))?);
+            }
+            let row_width = cells.len() - start;
+            if row_width == 0 || (height > 0 && row_width != width) {
+                return Err(line.error(
+                    line.text,
+                    ErrorKind::Invalid("grid rows must be the same length"),
+                ));
+            }
+            width = row_width;
+            height += 1;
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, (i, j): Pos) -> Option<&T> {
+        if i < self.height && j < self.width {
+            Some(&self.cells[i * self.width + j])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Moves `delta` away from `pos`, or `None` if that leaves the grid.
+    pub fn offset(&self, pos: Pos, delta: (isize, isize)) -> Option<Pos> {
+        offset(self.width, self.height, pos, delta)
+    }
+
+    /// Moves `delta` away from `pos`, wrapping around the edges as if the
+    /// grid were a torus.
+    pub fn wrapping_offset(&self, pos: Pos, delta: (isize, isize)) -> Pos {
+        wrapping_offset(self.width, self.height, pos, delta)
+    }
+
+    // The neighbour iterators only copy the dimensions out of the grid, so
+    // cells can be updated while walking them.
+
+    pub fn neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |d| offset(width, height, pos, *d))
+    }
+
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        SURROUNDING
+            .iter()
+            .filter_map(move |d| offset(width, height, pos, *d))
+    }
+
+    pub fn wrapping_neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        ORTHOGONAL
+            .iter()
+            .map(move |d| wrapping_offset(width, height, pos, *d))
+    }
+
+    pub fn wrapping_neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        SURROUNDING
+            .iter()
+            .map(move |d| wrapping_offset(width, height, pos, *d))
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a grid of single decimal digits.
+    pub fn parse_digits(s: &str) -> Result<Self, ParseError> {
+        Grid::parse(s, |c| c.to_digit(10).map(|d| d as u8))
+    }
+}
+
+fn offset(width: usize, height: usize, (i, j): Pos, (di, dj): (isize, isize)) -> Option<Pos> {
+    let i = (i as isize).checked_add(di)?;
+    let j = (j as isize).checked_add(dj)?;
+    if i >= 0 && j >= 0 && (i as usize) < height && (j as usize) < width {
+        Some((i as usize, j as usize))
+    } else {
+        None
+    }
+}
+
+fn wrapping_offset(width: usize, height: usize, (i, j): Pos, (di, dj): (isize, isize)) -> Pos {
+    (
+        (i as isize + di).rem_euclid(height as isize) as usize,
+        (j as isize + dj).rem_euclid(width as isize) as usize,
+    )
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): Pos) -> &T {
+        assert!(j < self.width, "column {} out of bounds", j);
+        &self.cells[i * self.width + j]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (i, j): Pos) -> &mut T {
+        assert!(j < self.width, "column {} out of bounds", j);
+        &mut self.cells[i * self.width + j]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::parse::ErrorKind;
+
+    #[test]
+    fn test_grid_parse() {
+        let grid = Grid::parse_digits("123\n456").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+
+        let e = Grid::parse_digits("123\n4.6").unwrap_err();
+        assert_eq!(
+            (e.line, e.column, e.kind),
+            (2, 2, ErrorKind::UnexpectedChar)
+        );
+        let e = Grid::parse_digits("123\n45").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "45"));
+        let e = Grid::parse_digits("").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::UnexpectedEnd);
+    }
+
+    #[test]
+    fn test_grid_neighbours() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(
+            grid.neighbours_4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours_4((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbours_8((0, 2)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 1), (1, 2)]
+        );
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_grid_wrapping() {
+        let grid = Grid::from_fn(4, 2, |(i, j)| i * 4 + j);
+        assert_eq!(grid.wrapping_offset((0, 3), (0, 1)), (0, 0));
+        assert_eq!(grid.wrapping_offset((0, 0), (-1, -1)), (1, 3));
+        assert_eq!(grid.offset((0, 3), (0, 1)), None);
+        assert_eq!(
+            grid.wrapping_neighbours_4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 3), (1, 0), (0, 1)]
+        );
+        assert_eq!(grid.wrapping_neighbours_8((1, 1)).count(), 8);
+        assert_eq!(grid.to_string(), "0123\n4567");
+    }
+}
@@ -1,3 +1,4 @@
+pub mod bench;
 pub mod day_1;
 pub mod day_10;
 pub mod day_11;
@@ -23,3 +24,11 @@ pub mod day_6;
 pub mod day_7;
 pub mod day_8;
 pub mod day_9;
+pub mod executor;
+pub mod generate;
+pub mod grid;
+pub mod parse;
+pub mod registry;
+pub mod solution;
+pub mod trace;
+pub mod verify;
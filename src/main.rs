@@ -0,0 +1,219 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
+
+use advent_of_code_2021::executor;
+use advent_of_code_2021::generate;
+use advent_of_code_2021::registry::{self, SOLUTIONS};
+use advent_of_code_2021::trace::{Silent, Stderr, Tracer};
+use advent_of_code_2021::verify::{self, Outcome};
+
+const USAGE: &str = "usage:
+    advent-of-code-2021 [--trace] <day> <part> [input]
+    advent-of-code-2021 [--trace] --all [--jobs <n>] [input-dir]
+    advent-of-code-2021 --verify [manifest]
+    advent-of-code-2021 --generate <day> <seed> <size>
+
+Reads the puzzle input from stdin when `input` is omitted or `-`. With --all,
+every day is run against `<input-dir>/day_N.txt` (default: src/input), several
+days at a time on `n` threads (default: one per CPU).
+--trace prints solver progress to stderr. --verify checks every answer in a
+manifest (default: src/input/answers.tsv) against its input. --generate prints a made-up input
+for a day, whose size depends on the day (for example, the number of lines).";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    match path {
+        None | Some("-") => {
+            let mut s = String::new();
+            io::stdin().read_to_string(&mut s)?;
+            Ok(s)
+        }
+        Some(path) => fs::read_to_string(path),
+    }
+}
+
+fn run_one(day: &str, part: &str, input: Option<&str>, tracer: &mut dyn Tracer) {
+    let solution = match day.parse().ok().and_then(registry::get) {
+        Some(solution) => solution,
+        None => {
+            eprintln!("unknown day {:?}", day);
+            usage();
+        }
+    };
+    let input = read_input(input).unwrap_or_else(|e| {
+        eprintln!("failed to read input: {}", e);
+        process::exit(1);
+    });
+
+    let parsed = solution.parse(&input).unwrap_or_else(|e| {
+        eprintln!("failed to parse day {} input: {}", solution.day(), e);
+        process::exit(1);
+    });
+    match part
+        .parse()
+        .ok()
+        .and_then(|p| solution.part_traced(p, &*parsed, tracer))
+    {
+        Some(answer) => println!("{}", answer),
+        None => {
+            eprintln!("day {} has no part {:?}", solution.day(), part);
+            usage();
+        }
+    }
+}
+
+fn run_all(input_dir: &Path, jobs: Option<usize>, trace: bool) {
+    // day 19 parallelises on the global pool too, so that's the one sized
+    let pool = match jobs {
+        Some(jobs) => executor::init_global(jobs),
+        None => executor::global(),
+    };
+    println!(
+        "{:>3}  {:>10}  {:>4}  {:<20}  {:>10}",
+        "day", "parse", "part", "answer", "time"
+    );
+
+    let start = Instant::now();
+    let mut total = Duration::default();
+    for run in executor::run_days(pool, SOLUTIONS, input_dir, trace) {
+        let parts = match run.parts {
+            Ok(parts) => parts,
+            Err(e) => {
+                println!("{:>3}  {}", run.day, e);
+                continue;
+            }
+        };
+        total += run.parse_time;
+
+        for part in parts {
+            total += part.elapsed;
+
+            // Some answers (day 13) are rendered across several lines.
+            let mut lines = part.answer.lines();
+            let first = lines.next().unwrap_or_default();
+            println!(
+                "{:>3}  {:>10}  {:>4}  {:<20}  {:>10}",
+                run.day,
+                if part.part == 1 {
+                    format!("{:.2?}", run.parse_time)
+                } else {
+                    String::new()
+                },
+                part.part,
+                first,
+                format!("{:.2?}", part.elapsed)
+            );
+            for line in lines {
+                println!("{:>3}  {:>10}  {:>4}  {}", "", "", "", line);
+            }
+        }
+    }
+
+    println!(
+        "total: {:.2?} ({:.2?} wall clock, {} threads)",
+        total,
+        start.elapsed(),
+        pool.threads()
+    );
+}
+
+fn run_verify(manifest: &Path) {
+    let entries = fs::read_to_string(manifest)
+        .map_err(|e| e.to_string())
+        .and_then(|s| verify::parse_manifest(&s).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            eprintln!("failed to read {}: {}", manifest.display(), e);
+            process::exit(1);
+        });
+    let base = manifest.parent().unwrap_or_else(|| Path::new(""));
+
+    println!(
+        "{:>3}  {:>4}  {:<16}  {:>10}  result",
+        "day", "part", "input", "time"
+    );
+    let mut failures = 0;
+    for report in verify::verify(&entries, base) {
+        if report.outcome != Outcome::Pass {
+            failures += 1;
+        }
+        println!(
+            "{:>3}  {:>4}  {:<16}  {:>10}  {}",
+            report.entry.day,
+            report.entry.part,
+            report.entry.input.display(),
+            format!("{:.2?}", report.elapsed),
+            report.outcome
+        );
+    }
+
+    println!("{} of {} passed", entries.len() - failures, entries.len());
+    if failures > 0 {
+        process::exit(1);
+    }
+}
+
+fn run_generate(day: &str, seed: &str, size: &str) {
+    let (seed, size) = match (seed.parse(), size.parse()) {
+        (Ok(seed), Ok(size)) => (seed, size),
+        _ => usage(),
+    };
+    match day
+        .parse()
+        .ok()
+        .and_then(|day| generate::generate(day, seed, size))
+    {
+        Some(input) => print!("{}", input),
+        None => {
+            eprintln!("unknown day {:?}", day);
+            usage();
+        }
+    }
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let mut args = args.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+
+    let trace = args.first() == Some(&"--trace");
+    if trace {
+        args.remove(0);
+    }
+    let tracer: &mut dyn Tracer = if trace { &mut Stderr } else { &mut Silent };
+
+    match args.as_slice() {
+        ["--all", rest @ ..] => {
+            let (jobs, rest) = match rest {
+                ["--jobs", jobs, rest @ ..] => match jobs.parse() {
+                    Ok(jobs) if jobs > 0 => (Some(jobs), rest),
+                    _ => usage(),
+                },
+                rest => (None, rest),
+            };
+            match rest {
+                [] => run_all(
+                    &Path::new(env!("CARGO_MANIFEST_DIR")).join("src/input"),
+                    jobs,
+                    trace,
+                ),
+                [dir] => run_all(&PathBuf::from(dir), jobs, trace),
+                _ => usage(),
+            }
+        }
+        ["--verify"] => {
+            run_verify(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src/input/answers.tsv"))
+        }
+        ["--verify", manifest] => run_verify(Path::new(manifest)),
+        ["--generate", day, seed, size] => run_generate(day, seed, size),
+        [day, part] => run_one(day, part, None, tracer),
+        [day, part, input] => run_one(day, part, Some(input), tracer),
+        _ => usage(),
+    }
+}
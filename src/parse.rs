@@ -0,0 +1,364 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    InvalidNumber,
+    UnexpectedChar,
+    UnknownKeyword,
+    MissingField,
+    UnexpectedEnd,
+    Invalid(&'static str),
+}
+
+/// An error in a puzzle input. `line` and `column` are 1-based, and `text` is
+/// the offending piece of input (empty when something was missing).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ErrorKind::InvalidNumber => write!(f, "invalid number {:?}", self.text),
+            ErrorKind::UnexpectedChar => write!(f, "unexpected character {:?}", self.text),
+            ErrorKind::UnknownKeyword => write!(f, "unknown keyword {:?}", self.text),
+            ErrorKind::MissingField => write!(f, "missing field"),
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::Invalid(why) if self.text.is_empty() => write!(f, "{}", why),
+            ErrorKind::Invalid(why) => write!(f, "{} ({:?})", why, self.text),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl ParseError {
+    pub fn end_of_input(line: usize) -> Self {
+        ParseError {
+            line,
+            column: 1,
+            text: String::new(),
+            kind: ErrorKind::UnexpectedEnd,
+        }
+    }
+}
+
+/// An error while reading input from a `BufRead`: either the reader itself
+/// failed, or what it produced didn't parse.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{}", e),
+            ReadError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(e: ParseError) -> Self {
+        ReadError::Parse(e)
+    }
+}
+
+/// A single line of input, along with its 1-based line number.
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(number: usize, text: &'a str) -> Self {
+        Line { number, text }
+    }
+
+    /// Builds an error pointing at `at`, which should be a slice of this
+    /// line. Anything else is reported at the start of the line.
+    pub fn error(&self, at: &str, kind: ErrorKind) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|o| *o <= self.text.len())
+            .unwrap_or(0);
+        ParseError {
+            line: self.number,
+            column: offset + 1,
+            text: at.to_owned(),
+            kind,
+        }
+    }
+
+    /// Builds an error pointing just past the end of the line.
+    pub fn missing(&self) -> ParseError {
+        self.error(&self.text[self.text.len()..], ErrorKind::MissingField)
+    }
+
+    pub fn number<T: FromStr>(&self, at: &'a str) -> Result<T, ParseError> {
+        let at = at.trim();
+        at.parse()
+            .map_err(|_| self.error(at, ErrorKind::InvalidNumber))
+    }
+
+    pub fn split_once(&self, at: &'a str, delim: &str) -> Result<(&'a str, &'a str), ParseError> {
+        at.split_once(delim).ok_or_else(|| match at.len() {
+            0 => self.missing(),
+            _ => self.error(at, ErrorKind::MissingField),
+        })
+    }
+
+    /// Pulls the next field out of `it`, failing if the line has run out.
+    pub fn field(&self, it: &mut impl Iterator<Item = &'a str>) -> Result<&'a str, ParseError> {
+        it.next().ok_or_else(|| self.missing())
+    }
+
+    /// Yields each character along with the slice of the line it came from,
+    /// so that it can be passed back to `error`.
+    pub fn chars(&self) -> impl Iterator<Item = (char, &'a str)> + 'a {
+        let text = self.text;
+        text.char_indices()
+            .map(move |(i, c)| (c, &text[i..i + c.len_utf8()]))
+    }
+
+    pub fn digit(&self, c: char, at: &str, radix: u32) -> Result<u32, ParseError> {
+        c.to_digit(radix)
+            .ok_or_else(|| self.error(at, ErrorKind::UnexpectedChar))
+    }
+
+    pub fn blank(&self) -> Result<(), ParseError> {
+        if self.text.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(self.text, ErrorKind::Invalid("expected a blank line")))
+        }
+    }
+}
+
+/// Like `str::lines`, but numbers each line and reports running out of input
+/// as an error.
+pub struct Lines<'a> {
+    inner: std::iter::Enumerate<std::str::Lines<'a>>,
+    last: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub fn expect(&mut self) -> Result<Line<'a>, ParseError> {
+        let last = self.last;
+        self.next()
+            .ok_or_else(|| ParseError::end_of_input(last + 1))
+    }
+
+    /// Consumes a line which must be empty.
+    pub fn blank(&mut self) -> Result<(), ParseError> {
+        self.expect()?.blank()
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Line<'a>> {
+        let (idx, text) = self.inner.next()?;
+        self.last = idx + 1;
+        Some(Line::new(idx + 1, text))
+    }
+}
+
+pub fn lines(s: &str) -> Lines<'_> {
+    Lines {
+        inner: s.lines().enumerate(),
+        last: 0,
+    }
+}
+
+/// Reads numbered lines out of a `BufRead` one at a time, reusing a single
+/// buffer so that memory use is bounded by the longest line.
+pub struct LineReader<R> {
+    reader: R,
+    buf: String,
+    last: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        LineReader {
+            reader,
+            buf: String::new(),
+            last: 0,
+        }
+    }
+
+    pub fn next_line(&mut self) -> io::Result<Option<Line<'_>>> {
+        self.buf.clear();
+        if self.reader.read_line(&mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        self.last += 1;
+
+        let text = self.buf.strip_suffix('\n').unwrap_or(&self.buf);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        Ok(Some(Line::new(self.last, text)))
+    }
+
+    pub fn expect(&mut self) -> Result<Line<'_>, ReadError> {
+        let last = self.last;
+        self.next_line()?
+            .ok_or_else(|| ParseError::end_of_input(last + 1).into())
+    }
+}
+
+/// Parses each line of a reader with `parse`, yielding the results as they
+/// are read.
+pub struct Records<R, F> {
+    lines: LineReader<R>,
+    parse: F,
+}
+
+impl<R, T, F> Iterator for Records<R, F>
+where
+    R: BufRead,
+    F: FnMut(&Line<'_>) -> Result<T, ParseError>,
+{
+    type Item = Result<T, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.lines.next_line() {
+            Ok(Some(line)) => Some((self.parse)(&line).map_err(ReadError::from)),
+            Ok(None) => None,
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+pub fn records<R, T, F>(reader: R, parse: F) -> Records<R, F>
+where
+    R: BufRead,
+    F: FnMut(&Line<'_>) -> Result<T, ParseError>,
+{
+    Records {
+        lines: LineReader::new(reader),
+        parse,
+    }
+}
+
+/// Hands the successfully read records to `f` as a plain iterator. Reading
+/// stops at the first error, which is returned in place of `f`'s result.
+pub fn process_records<T, U>(
+    records: impl Iterator<Item = Result<T, ReadError>>,
+    f: impl FnOnce(&mut dyn Iterator<Item = T>) -> U,
+) -> Result<U, ReadError> {
+    let mut error = None;
+    let mut ok = records.map_while(|r| r.map_err(|e| error = Some(e)).ok());
+    let out = f(&mut ok);
+    drop(ok);
+    match error {
+        Some(e) => Err(e),
+        None => Ok(out),
+    }
+}
+
+/// Parses a comma-separated list of numbers from a single line.
+pub fn numbers<T: FromStr>(line: &Line<'_>) -> Result<Vec<T>, ParseError> {
+    line.text.split(',').map(|v| line.number(v)).collect()
+}
+
+/// Parses a comma-separated list of numbers from the first line of `s`.
+pub fn number_list<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    numbers(&lines(s).expect()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        lines, number_list, process_records, records, ErrorKind, LineReader, ParseError, ReadError,
+    };
+
+    #[test]
+    fn test_error_positions() {
+        let mut it = lines("1,2\nfoo 12x");
+        it.next().unwrap();
+        let line = it.next().unwrap();
+        let (_, n) = line.split_once(line.text, " ").unwrap();
+        let e = line.number::<u8>(n).unwrap_err();
+        assert_eq!(
+            e,
+            ParseError {
+                line: 2,
+                column: 5,
+                text: "12x".to_owned(),
+                kind: ErrorKind::InvalidNumber,
+            }
+        );
+        assert_eq!(e.to_string(), "line 2, column 5: invalid number \"12x\"");
+
+        let e = it.expect().unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (3, 1, ErrorKind::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_number_list() {
+        assert_eq!(
+            number_list::<u8>("3,4,3,1,2\n").unwrap(),
+            vec![3, 4, 3, 1, 2]
+        );
+        let e = number_list::<u8>("3,4,,1").unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (1, 5, ErrorKind::InvalidNumber));
+        let e = number_list::<u8>("3,4,300").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (5, "300"));
+    }
+
+    #[test]
+    fn test_line_reader() {
+        let mut lines = LineReader::new("12\r\n\nab\n".as_bytes());
+        assert_eq!(lines.next_line().unwrap().unwrap().text, "12");
+        assert!(lines.next_line().unwrap().unwrap().blank().is_ok());
+        let line = lines.expect().unwrap();
+        assert_eq!((line.number, line.text), (3, "ab"));
+        match lines.expect() {
+            Err(ReadError::Parse(e)) => assert_eq!((e.line, e.kind), (4, ErrorKind::UnexpectedEnd)),
+            other => panic!("unexpected {:?}", other.map(|l| l.number)),
+        }
+    }
+
+    #[test]
+    fn test_process_records() {
+        let parsed = records("1\n2\n3".as_bytes(), |l| l.number::<u8>(l.text));
+        assert_eq!(process_records(parsed, |it| it.sum::<u8>()).unwrap(), 6);
+
+        let mut seen = 0;
+        let parsed = records("1\n2x\n3".as_bytes(), |l| l.number::<u8>(l.text));
+        match process_records(parsed, |it| it.for_each(|_| seen += 1)) {
+            Err(ReadError::Parse(e)) => assert_eq!((e.line, e.text.as_str()), (2, "2x")),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(seen, 1);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::solution::DynSolution;
+use crate::*;
+
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &day_1::Day1,
+    &day_2::Day2,
+    &day_3::Day3,
+    &day_4::Day4,
+    &day_5::Day5,
+    &day_6::Day6,
+    &day_7::Day7,
+    &day_8::Day8,
+    &day_9::Day9,
+    &day_10::Day10,
+    &day_11::Day11,
+    &day_12::Day12,
+    &day_13::Day13,
+    &day_14::Day14,
+    &day_15::Day15,
+    &day_16::Day16,
+    &day_17::Day17,
+    &day_18::Day18,
+    &day_19::Day19,
+    &day_20::Day20,
+    &day_21::Day21,
+    &day_22::Day22,
+    &day_23::Day23,
+    &day_24::Day24,
+    &day_25::Day25,
+];
+
+pub fn get(day: usize) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS.iter().copied().find(|s| s.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get, SOLUTIONS};
+
+    #[test]
+    fn test_registry_covers_every_day() {
+        let days = SOLUTIONS.iter().map(|s| s.day()).collect::<Vec<_>>();
+        assert_eq!(days, (1..=25).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_registry_dispatch() {
+        let day = get(1).unwrap();
+        let input = day
+            .parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263")
+            .unwrap();
+        assert_eq!(day.part(1, &*input).unwrap(), "7");
+        assert_eq!(day.part(2, &*input).unwrap(), "5");
+        assert!(day.part(3, &*input).is_none());
+
+        let day = get(25).unwrap();
+        let input = day.parse("v.\n.>").unwrap();
+        assert!(day.part(2, &*input).is_none());
+        assert!(get(26).is_none());
+
+        let e = get(1).unwrap().parse("199\n2x0").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "2x0"));
+    }
+}
@@ -0,0 +1,73 @@
+use std::any::Any;
+use std::fmt::Display;
+
+use crate::parse::ParseError;
+use crate::trace::{Silent, Tracer};
+
+/// A day's puzzle: a parse step shared by both parts, and the two parts
+/// themselves. Day 25 only has one part, so `part_2` returns `None` there.
+pub trait Solution {
+    const DAY: usize;
+
+    type Input: 'static;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError>;
+    fn part_1(input: &Self::Input) -> Self::Answer1;
+    fn part_2(input: &Self::Input) -> Option<Self::Answer2>;
+
+    /// Days which report progress override these; the rest ignore `tracer`.
+    fn part_1_traced(input: &Self::Input, _tracer: &mut dyn Tracer) -> Self::Answer1 {
+        Self::part_1(input)
+    }
+
+    fn part_2_traced(input: &Self::Input, _tracer: &mut dyn Tracer) -> Option<Self::Answer2> {
+        Self::part_2(input)
+    }
+}
+
+/// Object-safe view of a `Solution`, so that days with different input and
+/// answer types can live in the same registry.
+pub trait DynSolution: Sync {
+    fn day(&self) -> usize;
+    fn parse(&self, s: &str) -> Result<Box<dyn Any>, ParseError>;
+    fn part_1(&self, input: &dyn Any, tracer: &mut dyn Tracer) -> String;
+    fn part_2(&self, input: &dyn Any, tracer: &mut dyn Tracer) -> Option<String>;
+
+    fn part_traced(&self, part: usize, input: &dyn Any, tracer: &mut dyn Tracer) -> Option<String> {
+        match part {
+            1 => Some(self.part_1(input, tracer)),
+            2 => self.part_2(input, tracer),
+            _ => None,
+        }
+    }
+
+    fn part(&self, part: usize, input: &dyn Any) -> Option<String> {
+        self.part_traced(part, input, &mut Silent)
+    }
+}
+
+impl<T: Solution + Sync> DynSolution for T {
+    fn day(&self) -> usize {
+        T::DAY
+    }
+
+    fn parse(&self, s: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(T::parse(s)?))
+    }
+
+    fn part_1(&self, input: &dyn Any, tracer: &mut dyn Tracer) -> String {
+        T::part_1_traced(downcast::<T>(input), tracer).to_string()
+    }
+
+    fn part_2(&self, input: &dyn Any, tracer: &mut dyn Tracer) -> Option<String> {
+        T::part_2_traced(downcast::<T>(input), tracer).map(|a| a.to_string())
+    }
+}
+
+fn downcast<T: Solution>(input: &dyn Any) -> &T::Input {
+    input
+        .downcast_ref()
+        .unwrap_or_else(|| panic!("input was not parsed by day {}", T::DAY))
+}
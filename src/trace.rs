@@ -0,0 +1,66 @@
+use std::fmt;
+
+/// Receives progress output from the solvers that have something to say
+/// (scanner alignment on day 19, the burrow map on day 23, and so on).
+pub trait Tracer {
+    fn trace(&mut self, message: fmt::Arguments<'_>);
+
+    /// Lets solvers skip building expensive messages nobody will see.
+    fn enabled(&self) -> bool {
+        true
+    }
+}
+
+/// Discards everything.
+pub struct Silent;
+
+impl Tracer for Silent {
+    fn trace(&mut self, _message: fmt::Arguments<'_>) {}
+
+    fn enabled(&self) -> bool {
+        false
+    }
+}
+
+/// Writes each message to stderr on its own line.
+pub struct Stderr;
+
+impl Tracer for Stderr {
+    fn trace(&mut self, message: fmt::Arguments<'_>) {
+        eprintln!("{}", message);
+    }
+}
+
+/// Collects messages, one entry per `trace!`.
+impl Tracer for Vec<String> {
+    fn trace(&mut self, message: fmt::Arguments<'_>) {
+        self.push(message.to_string());
+    }
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($tracer:expr, $($arg:tt)*) => {
+        $crate::trace::Tracer::trace(&mut *$tracer, format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Silent, Tracer};
+
+    #[test]
+    fn test_trace_buffer() {
+        let mut buffer: Vec<String> = vec![];
+        let tracer: &mut dyn Tracer = &mut buffer;
+        trace!(tracer, "scanner {}", 3);
+        trace!(tracer, "done: {}/{}", 1, 2);
+        assert!(tracer.enabled());
+        assert_eq!(buffer, vec!["scanner 3", "done: 1/2"]);
+
+        let mut silent = Silent;
+        let tracer: &mut dyn Tracer = &mut silent;
+        trace!(tracer, "ignored");
+        assert!(!tracer.enabled());
+    }
+}
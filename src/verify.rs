@@ -0,0 +1,272 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::executor::panic_message;
+use crate::parse::{self, ErrorKind, Line, ParseError};
+use crate::registry;
+
+/// One expected answer: running `part` of `day` against `input` should
+/// produce `answer`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    pub day: usize,
+    pub part: usize,
+    pub input: PathBuf,
+    pub answer: String,
+}
+
+// Answers can span several lines (day 13), so they're stored escaped. A
+// trailing space is escaped too, since editors like to strip those.
+fn escape(answer: &str) -> String {
+    let mut out = String::new();
+    for c in answer.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    if out.ends_with(' ') {
+        out.pop();
+        out.push_str("\\s");
+    }
+    out
+}
+
+fn unescape<'a>(line: &Line<'a>, s: &'a str) -> Result<String, ParseError> {
+    let mut out = String::new();
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let (j, escaped) = chars
+            .next()
+            .ok_or_else(|| line.error(&s[i..], ErrorKind::Invalid("unfinished escape")))?;
+        out.push(match escaped {
+            '\\' => '\\',
+            'n' => '\n',
+            't' => '\t',
+            's' => ' ',
+            _ => {
+                let at = &s[i..j + escaped.len_utf8()];
+                return Err(line.error(at, ErrorKind::Invalid("unknown escape")));
+            }
+        });
+    }
+    Ok(out)
+}
+
+/// Parses a manifest of tab-separated `day part input answer` lines. Blank
+/// lines and lines starting with `#` are skipped.
+pub fn parse_manifest(s: &str) -> Result<Vec<Entry>, ParseError> {
+    let mut entries = vec![];
+    for line in parse::lines(s) {
+        if line.text.trim().is_empty() || line.text.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.text.splitn(4, '\t');
+        let day = line.number(line.field(&mut fields)?)?;
+        let part = line.number(line.field(&mut fields)?)?;
+        let input = line.field(&mut fields)?;
+        let answer = unescape(&line, line.field(&mut fields)?)?;
+        entries.push(Entry {
+            day,
+            part,
+            input: PathBuf::from(input),
+            answer,
+        });
+    }
+    Ok(entries)
+}
+
+pub fn format_manifest(entries: &[Entry]) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            entry.day,
+            entry.part,
+            entry.input.display(),
+            escape(&entry.answer)
+        ));
+    }
+    out
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Pass,
+    /// The solution ran, but gave this answer instead.
+    Mismatch(String),
+    /// The solution couldn't be run at all, for instance because the input
+    /// is missing or doesn't parse, or it panicked.
+    Fail(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Mismatch(actual) => write!(f, "mismatch: got {}", escape(actual)),
+            Outcome::Fail(why) => write!(f, "fail: {}", why),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Report<'a> {
+    pub entry: &'a Entry,
+    pub outcome: Outcome,
+    /// How long the part took to run, not counting reading and parsing.
+    pub elapsed: Duration,
+}
+
+type Parsed = Result<Box<dyn Any>, String>;
+
+fn run(
+    entry: &Entry,
+    base: &Path,
+    parsed: &mut HashMap<(usize, PathBuf), Parsed>,
+) -> Result<(String, Duration), String> {
+    let solution = registry::get(entry.day).ok_or_else(|| format!("unknown day {}", entry.day))?;
+    let path = base.join(&entry.input);
+    let input = parsed
+        .entry((entry.day, path.clone()))
+        .or_insert_with(|| {
+            let s = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            solution
+                .parse(&s)
+                .map_err(|e| format!("{}: {}", path.display(), e))
+        })
+        .as_ref()
+        .map_err(|e| e.clone())?;
+
+    let start = Instant::now();
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| solution.part(entry.part, &**input)))
+        .map_err(|e| format!("panicked: {}", panic_message(&*e)))?
+        .ok_or_else(|| format!("day {} has no part {}", entry.day, entry.part))?;
+    Ok((answer, start.elapsed()))
+}
+
+/// Runs every entry, reading inputs relative to `base`. Each input is only
+/// read and parsed once, however many entries use it.
+pub fn verify<'a>(entries: &'a [Entry], base: &Path) -> Vec<Report<'a>> {
+    let mut parsed = HashMap::new();
+    entries
+        .iter()
+        .map(|entry| {
+            let (outcome, elapsed) = match run(entry, base, &mut parsed) {
+                Ok((answer, elapsed)) if answer == entry.answer => (Outcome::Pass, elapsed),
+                Ok((answer, elapsed)) => (Outcome::Mismatch(answer), elapsed),
+                Err(why) => (Outcome::Fail(why), Duration::default()),
+            };
+            Report {
+                entry,
+                outcome,
+                elapsed,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::Path;
+    use std::{env, fs, process};
+
+    use super::{format_manifest, parse_manifest, verify, Entry, Outcome};
+    use crate::registry::SOLUTIONS;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let entries = vec![
+            Entry {
+                day: 1,
+                part: 2,
+                input: "day_1.txt".into(),
+                answer: "1158".to_owned(),
+            },
+            Entry {
+                day: 13,
+                part: 2,
+                input: "inputs/day 13.txt".into(),
+                answer: "# \\#\n ## ".to_owned(),
+            },
+        ];
+        let manifest = format_manifest(&entries);
+        assert_eq!(
+            manifest.lines().nth(1),
+            Some("13\t2\tinputs/day 13.txt\t# \\\\#\\n ##\\s")
+        );
+        assert_eq!(parse_manifest(&manifest).unwrap(), entries);
+        assert_eq!(
+            parse_manifest("# comment\n\n1\t1\tday_1.txt\t1184").unwrap()[0].answer,
+            "1184"
+        );
+
+        let e = parse_manifest("1\t1\tday_1.txt\t11\\x84").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 17, "\\x"));
+        let e = parse_manifest("1\tone\tday_1.txt\t1184").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (3, "one"));
+    }
+
+    #[test]
+    fn test_verify() {
+        // a board that never wins, so day 4 part 1 panics
+        let never_wins = env::temp_dir().join(format!("verify_day_4_{}.txt", process::id()));
+        fs::write(&never_wins, "1\n\n2").unwrap();
+        let manifest = format!(
+            "1\t1\tday_1.txt\t1184\n\
+             1\t2\tday_1.txt\t1\n\
+             1\t3\tday_1.txt\t1\n\
+             26\t1\tday_1.txt\t1\n\
+             2\t1\tmissing.txt\t1\n\
+             4\t1\t{}\t0\n\
+             1\t2\tday_1.txt\t1158\n",
+            never_wins.display()
+        );
+        let entries = parse_manifest(&manifest).unwrap();
+        let base = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/input");
+        let outcomes = verify(&entries, &base)
+            .into_iter()
+            .map(|r| r.outcome)
+            .collect::<Vec<_>>();
+        assert_eq!(outcomes[0], Outcome::Pass);
+        assert_eq!(outcomes[1], Outcome::Mismatch("1158".to_owned()));
+        assert_eq!(outcomes[2], Outcome::Fail("day 1 has no part 3".to_owned()));
+        assert_eq!(outcomes[3], Outcome::Fail("unknown day 26".to_owned()));
+        assert!(matches!(&outcomes[4], Outcome::Fail(why) if why.contains("missing.txt")));
+        assert_eq!(
+            outcomes[5],
+            Outcome::Fail("panicked: no board wins".to_owned())
+        );
+        // and the entries after it still run
+        assert_eq!(outcomes[6], Outcome::Pass);
+        fs::remove_file(never_wins).unwrap();
+    }
+
+    #[test]
+    fn test_answers_cover_every_day() {
+        let entries = parse_manifest(include_str!("input/answers.tsv")).unwrap();
+        let covered = entries
+            .iter()
+            .map(|e| (e.day, e.part))
+            .collect::<HashSet<_>>();
+        for solution in SOLUTIONS {
+            let day = solution.day();
+            assert!(covered.contains(&(day, 1)), "day {} part 1", day);
+            if day != 25 {
+                assert!(covered.contains(&(day, 2)), "day {} part 2", day);
+            }
+        }
+    }
+}